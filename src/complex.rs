@@ -0,0 +1,78 @@
+use std::mem::{align_of, size_of};
+
+use crate::sys;
+
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[repr(C)]
+pub struct Complex32 {
+    pub re: f32,
+    pub im: f32,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[repr(C)]
+pub struct Complex64 {
+    pub re: f64,
+    pub im: f64,
+}
+
+// Slices of these types are handed to MKL as `MKL_Complex8` / `MKL_Complex16` arrays.
+const _: () = assert!(size_of::<Complex32>() == size_of::<sys::MKL_Complex8>());
+const _: () = assert!(align_of::<Complex32>() == align_of::<sys::MKL_Complex8>());
+const _: () = assert!(size_of::<Complex64>() == size_of::<sys::MKL_Complex16>());
+const _: () = assert!(align_of::<Complex64>() == align_of::<sys::MKL_Complex16>());
+
+
+impl Complex32 {
+    pub const fn new(re: f32, im: f32) -> Self {
+        Self { re, im }
+    }
+}
+
+impl Complex64 {
+    pub const fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+}
+
+impl From<Complex32> for sys::MKL_Complex8 {
+    fn from(value: Complex32) -> Self {
+        Self { real: value.re, imag: value.im }
+    }
+}
+
+impl From<Complex64> for sys::MKL_Complex16 {
+    fn from(value: Complex64) -> Self {
+        Self { real: value.re, imag: value.im }
+    }
+}
+
+
+#[cfg(feature = "num-complex")]
+impl From<num_complex::Complex32> for Complex32 {
+    fn from(value: num_complex::Complex32) -> Self {
+        Self::new(value.re, value.im)
+    }
+}
+
+#[cfg(feature = "num-complex")]
+impl From<Complex32> for num_complex::Complex32 {
+    fn from(value: Complex32) -> Self {
+        Self::new(value.re, value.im)
+    }
+}
+
+#[cfg(feature = "num-complex")]
+impl From<num_complex::Complex64> for Complex64 {
+    fn from(value: num_complex::Complex64) -> Self {
+        Self::new(value.re, value.im)
+    }
+}
+
+#[cfg(feature = "num-complex")]
+impl From<Complex64> for num_complex::Complex64 {
+    fn from(value: Complex64) -> Self {
+        Self::new(value.re, value.im)
+    }
+}
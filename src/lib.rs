@@ -1,4 +1,4 @@
-use std::{ptr, slice, ffi::c_void, mem::size_of, ops::{Deref, DerefMut}};
+use std::{fmt, ptr, slice, vec, cell::RefCell, ffi::{c_int, c_void}, marker::PhantomData, mem::{align_of, size_of, ManuallyDrop, MaybeUninit}, ops::{Deref, DerefMut, Index, IndexMut}, slice::SliceIndex};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 
 
@@ -8,8 +8,15 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 #[allow(non_camel_case_types)]
 mod sys;
 
+mod complex;
+pub mod vml;
+pub mod vsl;
+
+pub use complex::{Complex32, Complex64};
+
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum VmlAccuracyMode {
     HighAccuracy = sys::VML_HA,
@@ -18,6 +25,7 @@ pub enum VmlAccuracyMode {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum VmlFtzdazMode {
     On = sys::VML_FTZDAZ_ON,
@@ -26,6 +34,7 @@ pub enum VmlFtzdazMode {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum VmlErrorMode {
     Ignore = sys::VML_ERRMODE_IGNORE,
@@ -39,6 +48,7 @@ pub enum VmlErrorMode {
 
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VmlMode {
     pub accuracy: VmlAccuracyMode,
     pub ftzdaz: VmlFtzdazMode,
@@ -93,10 +103,10 @@ impl TryFrom<u32> for VmlMode {
 pub enum VmlStatus {
     Ok = sys::VML_STATUS_OK,
     AccuracyWarning = sys::VML_STATUS_ACCURACYWARNING,
-    AccuracyUnsupported = sys::VML_STATUS_BADSIZE,
-    NullPointer = sys::VML_STATUS_BADMEM,
-    OutOfRange = sys::VML_STATUS_ERRDOM,
-    NanValue = sys::VML_STATUS_SING,
+    BadSize = sys::VML_STATUS_BADSIZE,
+    BadMemory = sys::VML_STATUS_BADMEM,
+    DomainError = sys::VML_STATUS_ERRDOM,
+    Singularity = sys::VML_STATUS_SING,
     Overflow = sys::VML_STATUS_OVERFLOW,
     Underflow = sys::VML_STATUS_UNDERFLOW,
 }
@@ -106,6 +116,7 @@ pub type VslStreamState = c_void;
 
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum VslBrng {
     MCG31 = sys::VSL_BRNG_MCG31,
@@ -126,6 +137,118 @@ pub enum VslBrng {
     ARS5 = sys::VSL_BRNG_ARS5,
 }
 
+/// A basic generator together with its index within a BRNG family.
+///
+/// MT2203 and Wichmann-Hill are families of 6024 and 273 generators whose ids are the base id plus
+/// the index; every other BRNG only has index 0.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "i32", into = "i32"))]
+pub struct BrngId {
+    brng: VslBrng,
+    index: u32,
+}
+
+impl BrngId {
+    pub fn new(brng: VslBrng, index: u32) -> Result<Self, VslError> {
+        match index < Self::family_size(brng) {
+            true => Ok(Self { brng, index }),
+            false => Err(VslError::InvalidBrngIndex),
+        }
+    }
+
+    pub fn family_size(brng: VslBrng) -> u32 {
+        match brng {
+            VslBrng::MT2203 => 6024,
+            VslBrng::WichmannHill => 273,
+            _ => 1,
+        }
+    }
+
+    pub fn brng(&self) -> VslBrng {
+        self.brng
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+impl From<VslBrng> for BrngId {
+    fn from(brng: VslBrng) -> Self {
+        Self { brng, index: 0 }
+    }
+}
+
+impl From<BrngId> for i32 {
+    fn from(id: BrngId) -> Self {
+        i32::from(id.brng) + id.index as i32
+    }
+}
+
+impl TryFrom<i32> for BrngId {
+    type Error = VslError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        let index = value.rem_euclid(sys::VSL_BRNG_INC);
+        match VslBrng::try_from(value - index) {
+            Ok(brng) => Self::new(brng, index as u32),
+            Err(_) => Err(VslError::InvalidBrngIndex),
+        }
+    }
+}
+
+/// A generator added to MKL's BRNG table with `vsl::register_brng`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RegisteredBrng {
+    id: i32,
+}
+
+impl RegisteredBrng {
+    pub(crate) fn new(id: i32) -> Self {
+        Self { id }
+    }
+}
+
+impl From<RegisteredBrng> for i32 {
+    fn from(brng: RegisteredBrng) -> Self {
+        brng.id
+    }
+}
+
+/// Any generator a stream can be created with: a built-in one or one registered at run time.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StreamBrng {
+    BuiltIn(BrngId),
+    Registered(RegisteredBrng),
+}
+
+impl From<VslBrng> for StreamBrng {
+    fn from(brng: VslBrng) -> Self {
+        Self::BuiltIn(brng.into())
+    }
+}
+
+impl From<BrngId> for StreamBrng {
+    fn from(id: BrngId) -> Self {
+        Self::BuiltIn(id)
+    }
+}
+
+impl From<RegisteredBrng> for StreamBrng {
+    fn from(brng: RegisteredBrng) -> Self {
+        Self::Registered(brng)
+    }
+}
+
+impl From<StreamBrng> for i32 {
+    fn from(brng: StreamBrng) -> Self {
+        match brng {
+            StreamBrng::BuiltIn(id) => id.into(),
+            StreamBrng::Registered(brng) => brng.into(),
+        }
+    }
+}
+
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
 #[repr(i32)]
@@ -165,146 +288,1052 @@ pub enum VslError {
     BadMemoryFormat = sys::VSL_RNG_ERROR_BAD_MEM_FORMAT,
 }
 
+impl fmt::Display for VslError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodUniform {
+    Fast = sys::VSL_RNG_METHOD_UNIFORM_STD,
+    Accurate = sys::VSL_RNG_METHOD_UNIFORM_STD_ACCURATE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodUniformDiscrete {
+    Fast = sys::VSL_RNG_METHOD_UNIFORM_STD,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodUniformBits {
+    Std = sys::VSL_RNG_METHOD_UNIFORMBITS_STD,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodUniformBits32 {
+    Std = sys::VSL_RNG_METHOD_UNIFORMBITS32_STD,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodUniformBits64 {
+    Std = sys::VSL_RNG_METHOD_UNIFORMBITS64_STD,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodBernoulli {
+    Icdf = sys::VSL_RNG_METHOD_BERNOULLI_ICDF,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodGeometric {
+    Icdf = sys::VSL_RNG_METHOD_GEOMETRIC_ICDF,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodBinomial {
+    Btpe = sys::VSL_RNG_METHOD_BINOMIAL_BTPE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodHypergeometric {
+    H2pe = sys::VSL_RNG_METHOD_HYPERGEOMETRIC_H2PE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodPoisson {
+    Ptpe = sys::VSL_RNG_METHOD_POISSON_PTPE,
+    PoisNorm = sys::VSL_RNG_METHOD_POISSON_POISNORM,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodPoissonV {
+    PoisNorm = sys::VSL_RNG_METHOD_POISSONV_POISNORM,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodNegBinomial {
+    Nbar = sys::VSL_RNG_METHOD_NEGBINOMIAL_NBAR,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodMultinomial {
+    MultPoisson = sys::VSL_RNG_METHOD_MULTINOMIAL_MULTPOISSON,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodGaussian {
+    BoxMuller = sys::VSL_RNG_METHOD_GAUSSIAN_BOXMULLER,
+    BoxMuller2 = sys::VSL_RNG_METHOD_GAUSSIAN_BOXMULLER2,
+    Icdf = sys::VSL_RNG_METHOD_GAUSSIAN_ICDF,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodGaussianMv {
+    BoxMuller = sys::VSL_RNG_METHOD_GAUSSIANMV_BOXMULLER,
+    BoxMuller2 = sys::VSL_RNG_METHOD_GAUSSIANMV_BOXMULLER2,
+    Icdf = sys::VSL_RNG_METHOD_GAUSSIANMV_ICDF,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum VslMatrixStorage {
+    Full = sys::VSL_MATRIX_STORAGE_FULL,
+    Packed = sys::VSL_MATRIX_STORAGE_PACKED,
+    Diagonal = sys::VSL_MATRIX_STORAGE_DIAGONAL,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodExponential {
+    Icdf = sys::VSL_RNG_METHOD_EXPONENTIAL_ICDF,
+    IcdfAccurate = sys::VSL_RNG_METHOD_EXPONENTIAL_ICDF_ACCURATE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodLaplace {
+    Icdf = sys::VSL_RNG_METHOD_LAPLACE_ICDF,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodWeibull {
+    Icdf = sys::VSL_RNG_METHOD_WEIBULL_ICDF,
+    IcdfAccurate = sys::VSL_RNG_METHOD_WEIBULL_ICDF_ACCURATE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodCauchy {
+    Icdf = sys::VSL_RNG_METHOD_CAUCHY_ICDF,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodRayleigh {
+    Icdf = sys::VSL_RNG_METHOD_RAYLEIGH_ICDF,
+    IcdfAccurate = sys::VSL_RNG_METHOD_RAYLEIGH_ICDF_ACCURATE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodLognormal {
+    BoxMuller2 = sys::VSL_RNG_METHOD_LOGNORMAL_BOXMULLER2,
+    Icdf = sys::VSL_RNG_METHOD_LOGNORMAL_ICDF,
+    BoxMuller2Accurate = sys::VSL_RNG_METHOD_LOGNORMAL_BOXMULLER2_ACCURATE,
+    IcdfAccurate = sys::VSL_RNG_METHOD_LOGNORMAL_ICDF_ACCURATE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodGumbel {
+    Icdf = sys::VSL_RNG_METHOD_GUMBEL_ICDF,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodGamma {
+    GNorm = sys::VSL_RNG_METHOD_GAMMA_GNORM,
+    GNormAccurate = sys::VSL_RNG_METHOD_GAMMA_GNORM_ACCURATE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodBeta {
+    Cja = sys::VSL_RNG_METHOD_BETA_CJA,
+    CjaAccurate = sys::VSL_RNG_METHOD_BETA_CJA_ACCURATE,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i32)]
+pub enum VslRngMethodChiSquare {
+    Chi2Gamma = sys::VSL_RNG_METHOD_CHISQUARE_CHI2GAMMA,
+}
+
+
+pub fn malloc<T>(n: usize, align: usize) -> *mut T {
+    unsafe { sys::MKL_malloc(n * size_of::<T>(), align.try_into().unwrap()) as *mut T }
+}
+
+pub fn free<T>(ptr: *const T) {
+    unsafe { sys::MKL_free(ptr as *mut c_void) };
+}
+
+pub fn free_buffers() {
+    unsafe { sys::MKL_Free_Buffers() };
+}
+
+
+pub fn vml_get_mode() -> VmlMode {
+    VmlMode::try_from(unsafe { sys::vmlGetMode() }).unwrap()
+}
+
+pub fn vml_set_mode(new_mode: VmlMode) -> VmlMode {
+    VmlMode::try_from(unsafe { sys::vmlSetMode(new_mode.into()) }).unwrap()
+}
+
+pub fn vml_get_err_status() -> VmlStatus {
+    VmlStatus::try_from(unsafe { sys::vmlGetErrStatus() }).unwrap()
+}
+
+pub fn vml_set_err_status(new_status: VmlStatus) -> VmlStatus {
+    VmlStatus::try_from(unsafe { sys::vmlSetErrStatus(new_status.into()) }).unwrap()
+}
+
+pub fn vml_clear_err_status() -> VmlStatus {
+    VmlStatus::try_from(unsafe { sys::vmlClearErrStatus() }).unwrap()
+}
+
+
+/// Details of a failing element, passed to callbacks installed with `vml_set_error_callback`.
+pub struct VmlErrorContext<'a> {
+    raw: &'a mut sys::DefVmlErrorContext,
+}
+
+impl VmlErrorContext<'_> {
+    pub fn status(&self) -> Option<VmlStatus> {
+        VmlStatus::try_from(self.raw.iCode).ok()
+    }
+
+    /// Position of the failing element, or `None` if MKL reports a negative index.
+    pub fn index(&self) -> Option<usize> {
+        self.raw.iIndex.try_into().ok()
+    }
+
+    pub fn function_name(&self) -> &str {
+        let len = self.raw.iFuncNameLen.clamp(0, self.raw.cFuncName.len() as c_int) as usize;
+        let name = unsafe { slice::from_raw_parts(self.raw.cFuncName.as_ptr() as *const u8, len) };
+        std::str::from_utf8(name).unwrap_or("")
+    }
+
+    pub fn arg1(&self) -> f64 {
+        self.raw.dbA1
+    }
+
+    pub fn arg1_im(&self) -> f64 {
+        self.raw.dbA1Im
+    }
+
+    pub fn arg2(&self) -> f64 {
+        self.raw.dbA2
+    }
+
+    pub fn arg2_im(&self) -> f64 {
+        self.raw.dbA2Im
+    }
+
+    pub fn result1(&self) -> f64 {
+        self.raw.dbR1
+    }
+
+    pub fn result1_im(&self) -> f64 {
+        self.raw.dbR1Im
+    }
+
+    pub fn result2(&self) -> f64 {
+        self.raw.dbR2
+    }
+
+    pub fn result2_im(&self) -> f64 {
+        self.raw.dbR2Im
+    }
+
+    pub fn set_result1(&mut self, value: f64) {
+        self.raw.dbR1 = value;
+    }
+
+    pub fn set_result1_im(&mut self, value: f64) {
+        self.raw.dbR1Im = value;
+    }
+
+    pub fn set_result2(&mut self, value: f64) {
+        self.raw.dbR2 = value;
+    }
+
+    pub fn set_result2_im(&mut self, value: f64) {
+        self.raw.dbR2Im = value;
+    }
+}
+
+
+type VmlErrorCallback = Box<dyn FnMut(&mut VmlErrorContext)>;
+
+thread_local! {
+    static VML_ERROR_CALLBACK: RefCell<Option<VmlErrorCallback>> = const { RefCell::new(None) };
+}
+
+unsafe extern "C" fn vml_error_callback_trampoline(context: *mut sys::DefVmlErrorContext) -> c_int {
+    let Some(raw) = (unsafe { context.as_mut() }) else {
+        return 0;
+    };
+
+    VML_ERROR_CALLBACK.with(|callback| {
+        // A VML error raised from inside the callback itself is left to MKL's default handling.
+        if let Ok(mut callback) = callback.try_borrow_mut() {
+            if let Some(callback) = callback.as_mut() {
+                callback(&mut VmlErrorContext { raw });
+            }
+        }
+    });
+
+    0
+}
+
+/// Installs `callback` as the VML error callback of the current thread until the returned
+/// guard is dropped. The callback only runs while the error mode includes
+/// `VmlErrorMode::Callback`, and it may overwrite the result of the failing element.
+/// Panicking inside the callback aborts the process.
+pub fn vml_set_error_callback(callback: impl FnMut(&mut VmlErrorContext) + 'static) -> VmlErrorCallbackGuard {
+    let previous_callback = VML_ERROR_CALLBACK.with(|cell| cell.replace(Some(Box::new(callback))));
+    let previous = unsafe { sys::vmlGetErrorCallBack() };
+    unsafe { sys::vmlSetErrorCallBack(Some(vml_error_callback_trampoline)) };
+
+    VmlErrorCallbackGuard {
+        previous,
+        previous_callback,
+        _not_send: PhantomData,
+    }
+}
+
+#[must_use]
+pub struct VmlErrorCallbackGuard {
+    previous: sys::VMLErrorCallBack,
+    previous_callback: Option<VmlErrorCallback>,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for VmlErrorCallbackGuard {
+    fn drop(&mut self) {
+        match self.previous {
+            Some(previous) => unsafe { sys::vmlSetErrorCallBack(Some(previous)) },
+            None => unsafe { sys::vmlClearErrorCallBack() },
+        };
+        VML_ERROR_CALLBACK.with(|cell| cell.replace(self.previous_callback.take()));
+    }
+}
+
+
+pub fn with_vml_mode<R>(mode: VmlMode, f: impl FnOnce() -> R) -> R {
+    let _guard = VmlModeGuard::new(mode);
+    f()
+}
+
+
+/// Sets the VML mode and restores the previous one when dropped, including on unwind.
+///
+/// The VML mode is per thread, so the guard is `!Send` to keep the restore on the thread
+/// that made the change.
+#[must_use]
+#[derive(Debug)]
+pub struct VmlModeGuard {
+    previous: VmlMode,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl VmlModeGuard {
+    pub fn new(mode: VmlMode) -> Self {
+        Self {
+            previous: vml_set_mode(mode),
+            _not_send: PhantomData,
+        }
+    }
+
+    pub fn previous(&self) -> VmlMode {
+        self.previous
+    }
+}
+
+impl Drop for VmlModeGuard {
+    fn drop(&mut self) {
+        vml_set_mode(self.previous);
+    }
+}
+
+
+pub fn vsl_new_stream(brng: impl Into<StreamBrng>, seed: u32) -> Result<*mut VslStreamState, VslError> {
+    let mut stream: *mut VslStreamState = ptr::null_mut();
+    match unsafe { sys::vslNewStream(&mut stream as *mut *mut VslStreamState, brng.into().into(), seed) } {
+        sys::VSL_STATUS_OK => Ok(stream),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+pub fn vsl_delete_stream(stream: &mut *mut VslStreamState) -> Result<(), VslError> {
+    match unsafe { sys::vslDeleteStream(stream as *mut *mut VslStreamState) } {
+        sys::VSL_STATUS_OK => Ok(()),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+pub(crate) fn vsl_copy_stream(stream: *mut VslStreamState) -> Result<*mut VslStreamState, VslError> {
+    let mut new_stream: *mut VslStreamState = ptr::null_mut();
+    match unsafe { sys::vslCopyStream(&mut new_stream as *mut *mut VslStreamState, stream) } {
+        sys::VSL_STATUS_OK => Ok(new_stream),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+pub(crate) fn vsl_copy_stream_state(dest: *mut VslStreamState, src: *mut VslStreamState) -> Result<(), VslError> {
+    match unsafe { sys::vslCopyStreamState(dest, src) } {
+        sys::VSL_STATUS_OK => Ok(()),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+
+pub fn vs_rng_uniform(method: VslRngMethodUniform, stream: *mut VslStreamState, n: i32, r: *mut f32, a: f32, b: f32) -> Result<(), VslError> {
+    match unsafe { sys::vsRngUniform(method.into(), stream, n, r, a, b) } {
+        sys::VSL_STATUS_OK => Ok(()),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+pub fn vd_rng_uniform(method: VslRngMethodUniform, stream: *mut VslStreamState, n: i32, r: *mut f64, a: f64, b: f64) -> Result<(), VslError> {
+    match unsafe { sys::vdRngUniform(method.into(), stream, n, r, a, b) } {
+        sys::VSL_STATUS_OK => Ok(()),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+pub fn vi_rng_uniform(method: VslRngMethodUniformDiscrete, stream: *mut VslStreamState, n: i32, r: *mut i32, a: i32, b: i32) -> Result<(), VslError> {
+    match unsafe { sys::viRngUniform(method.into(), stream, n, r, a, b) } {
+        sys::VSL_STATUS_OK => Ok(()),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+
+const MKL_REALLOC_ALIGN: usize = 64;
+
+/// An MKL-allocated array of `len` elements.
+///
+/// A `Buffer` owns its allocation like a `Box<[T]>`, so it is `Send` and `Sync` whenever `T` is.
+/// Writes go through `&mut self`; `as_mut_ptr` only hands out a raw pointer, and writing through
+/// it while the buffer is shared is up to the caller.
+pub struct Buffer<T> {
+    data: *mut T,
+    len: usize,
+    capacity: usize,
+    align: usize,
+}
+
+unsafe impl<T: Send> Send for Buffer<T> {}
+unsafe impl<T: Sync> Sync for Buffer<T> {}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BufferError {
+    CapacityOverflow,
+    InvalidAlignment(usize),
+    OutOfMemory { size: usize, align: usize },
+}
+
+/// Types for which the all-zero bit pattern is a valid value, so `Buffer::zeroed` can use
+/// `MKL_calloc` without initializing the elements itself.
+///
+/// # Safety
+///
+/// Implementors must be valid when every byte is zero.
+pub unsafe trait Zeroable {}
+
+unsafe impl Zeroable for i8 {}
+unsafe impl Zeroable for i16 {}
+unsafe impl Zeroable for i32 {}
+unsafe impl Zeroable for i64 {}
+unsafe impl Zeroable for isize {}
+unsafe impl Zeroable for u8 {}
+unsafe impl Zeroable for u16 {}
+unsafe impl Zeroable for u32 {}
+unsafe impl Zeroable for u64 {}
+unsafe impl Zeroable for usize {}
+unsafe impl Zeroable for f32 {}
+unsafe impl Zeroable for f64 {}
+unsafe impl Zeroable for Complex32 {}
+unsafe impl Zeroable for Complex64 {}
+
+impl<T> Buffer<T> {
+    fn size_of_capacity(capacity: usize) -> Result<usize, BufferError> {
+        match capacity.checked_mul(size_of::<T>()) {
+            Some(size) if size <= isize::MAX as usize => Ok(size),
+            _ => Err(BufferError::CapacityOverflow),
+        }
+    }
+
+    // Empty buffers get an aligned dangling pointer instead of an MKL allocation.
+    fn try_allocate(len: usize, align: usize, zeroed: bool) -> Result<*mut T, BufferError> {
+        let size = Self::size_of_capacity(len)?;
+        if size == 0 {
+            return Ok(align as *mut T);
+        }
+
+        let mkl_align = c_int::try_from(align).map_err(|_| BufferError::InvalidAlignment(align))?;
+        let data = match zeroed {
+            true => unsafe { sys::MKL_calloc(len, size_of::<T>(), mkl_align) },
+            false => unsafe { sys::MKL_malloc(size, mkl_align) },
+        };
+        match data.is_null() {
+            true => Err(BufferError::OutOfMemory { size, align }),
+            false => Ok(data as *mut T),
+        }
+    }
+
+    fn try_from_fn(len: usize, align: usize, mut f: impl FnMut(usize) -> T) -> Result<Self, BufferError> {
+        let mut buffer = Self::try_uninit(len, align)?;
+        for (i, x) in buffer.iter_mut().enumerate() {
+            x.write(f(i));
+        }
+        Ok(unsafe { buffer.assume_init() })
+    }
+
+    pub fn try_new(len: usize, align: usize) -> Result<Self, BufferError> where T: Default {
+        Self::try_from_fn(len, align, |_| T::default())
+    }
+
+    pub fn new(len: usize, align: usize) -> Self where T: Default {
+        Self::try_new(len, align).unwrap()
+    }
+
+    pub fn new_align_64(len: usize) -> Self where T: Default {
+        Self::new(len, 64)
+    }
+
+    fn try_with_capacity(capacity: usize, align: usize, zeroed: bool) -> Result<Self, BufferError> {
+        if !align.is_power_of_two() {
+            return Err(BufferError::InvalidAlignment(align));
+        }
+        let align = align.max(align_of::<T>());
+        let data = Self::try_allocate(capacity, align, zeroed)?;
+        Ok(Self { data, len: 0, capacity, align })
+    }
+
+    pub fn try_zeroed(len: usize, align: usize) -> Result<Self, BufferError> where T: Zeroable {
+        let mut buffer = Self::try_with_capacity(len, align, true)?;
+        buffer.len = len;
+        Ok(buffer)
+    }
+
+    pub fn zeroed(len: usize, align: usize) -> Self where T: Zeroable {
+        Self::try_zeroed(len, align).unwrap()
+    }
+
+    pub fn from_elem(elem: T, len: usize, align: usize) -> Self where T: Clone {
+        Self::try_from_fn(len, align, |_| elem.clone()).unwrap()
+    }
+
+    pub fn from_slice(slice: &[T], align: usize) -> Self where T: Clone {
+        Self::try_from_fn(slice.len(), align, |i| slice[i].clone()).unwrap()
+    }
+
+    pub fn try_uninit(len: usize, align: usize) -> Result<Buffer<MaybeUninit<T>>, BufferError> {
+        let mut buffer = Buffer::try_with_capacity(len, align, false)?;
+        buffer.len = len;
+        Ok(buffer)
+    }
+
+    pub fn uninit(len: usize, align: usize) -> Buffer<MaybeUninit<T>> {
+        Self::try_uninit(len, align).unwrap()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn alignment(&self) -> usize {
+        self.align
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        debug_assert!((self.data as usize).is_multiple_of(self.align));
+        self.data
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data, self.len) }
+    }
+
+    pub fn as_mut_ptr(&self) -> *mut T {
+        self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl<T> Buffer<MaybeUninit<T>> {
+    /// # Safety
+    ///
+    /// Every element must have been initialized.
+    pub unsafe fn assume_init(self) -> Buffer<T> {
+        let buffer = ManuallyDrop::new(self);
+        Buffer { data: buffer.data as *mut T, len: buffer.len, capacity: buffer.capacity, align: buffer.align }
+    }
+}
+
+impl<T> Buffer<T> {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // MKL_realloc returns 64-byte aligned blocks, so larger alignments always move to a fresh
+    // allocation. The fields are only updated once the new block holds the elements.
+    fn try_grow(&mut self, capacity: usize) -> Result<(), BufferError> {
+        let size = Self::size_of_capacity(capacity)?;
+        let allocated = self.capacity * size_of::<T>() != 0;
+        let data = match size != 0 && allocated && self.align <= MKL_REALLOC_ALIGN {
+            true => self.try_realloc(capacity, size)?,
+            false => {
+                let data = Self::try_allocate(capacity, self.align, false)?;
+                unsafe { ptr::copy_nonoverlapping(self.data, data, self.len) };
+                if allocated {
+                    free(self.data);
+                }
+                data
+            }
+        };
+        self.data = data;
+        self.capacity = capacity;
+        Ok(())
+    }
+
+    fn try_realloc(&self, capacity: usize, size: usize) -> Result<*mut T, BufferError> {
+        let data = unsafe { sys::MKL_realloc(self.data as *mut c_void, size) } as *mut T;
+        if data.is_null() {
+            return Err(BufferError::OutOfMemory { size, align: self.align });
+        }
+        if (data as usize).is_multiple_of(self.align) {
+            return Ok(data);
+        }
+
+        // The old block is already gone, so a failure here cannot be reported as an error.
+        let layout = std::alloc::Layout::from_size_align(size, self.align).unwrap();
+        let aligned = Self::try_allocate(capacity, self.align, false).unwrap_or_else(|_| std::alloc::handle_alloc_error(layout));
+        unsafe { ptr::copy_nonoverlapping(data, aligned, self.len) };
+        free(data);
+        Ok(aligned)
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), BufferError> {
+        let required = self.len.checked_add(additional).ok_or(BufferError::CapacityOverflow)?;
+        match required > self.capacity {
+            true => self.try_grow(required.max(self.capacity.saturating_mul(2))),
+            false => Ok(()),
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.try_reserve(additional).unwrap()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.reserve(1);
+        unsafe { self.data.add(self.len).write(value) };
+        self.len += 1;
+    }
+
+    pub fn extend_from_slice(&mut self, other: &[T]) where T: Clone {
+        self.reserve(other.len());
+        for value in other {
+            unsafe { self.data.add(self.len).write(value.clone()) };
+            self.len += 1;
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            let tail = ptr::slice_from_raw_parts_mut(unsafe { self.data.add(len) }, self.len - len);
+            self.len = len;
+            unsafe { ptr::drop_in_place(tail) };
+        }
+    }
+
+    pub fn resize(&mut self, len: usize, value: T) where T: Clone {
+        if len > self.len {
+            self.reserve(len - self.len);
+            while self.len < len {
+                unsafe { self.data.add(self.len).write(value.clone()) };
+                self.len += 1;
+            }
+        } else {
+            self.truncate(len);
+        }
+    }
+}
+
+impl<T> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+        if self.capacity * size_of::<T>() != 0 {
+            free(self.data);
+        }
+    }
+}
+
+impl<T> Deref for Buffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for Buffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, I: SliceIndex<[T]>> Index<I> for Buffer<T> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        &self.as_slice()[index]
+    }
+}
+
+impl<T, I: SliceIndex<[T]>> IndexMut<I> for Buffer<T> {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        &mut self.as_mut_slice()[index]
+    }
+}
+
+impl<T> AsRef<[T]> for Buffer<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> AsMut<[T]> for Buffer<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Buffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for Buffer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq> Eq for Buffer<T> {}
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
-#[repr(i32)]
-pub enum VslRngMethodUniform {
-    Fast = sys::VSL_RNG_METHOD_UNIFORM_STD,
-    Accurate = sys::VSL_RNG_METHOD_UNIFORM_STD_ACCURATE,
+impl<T: PartialEq> PartialEq<[T]> for Buffer<T> {
+    fn eq(&self, other: &[T]) -> bool {
+        self.as_slice() == other
+    }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
-#[repr(i32)]
-pub enum VslRngMethodUniformDiscrete {
-    Fast = sys::VSL_RNG_METHOD_UNIFORM_STD,
+impl<T: PartialEq> PartialEq<Vec<T>> for Buffer<T> {
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.as_slice() == other.as_slice()
+    }
 }
 
+impl<T: Clone> Clone for Buffer<T> {
+    fn clone(&self) -> Self {
+        let mut buffer = Self::try_with_capacity(self.len, self.align, false).unwrap();
+        buffer.extend_from_slice(self);
+        buffer
+    }
+}
 
-pub fn malloc<T>(n: usize, align: usize) -> *mut T {
-    unsafe { sys::MKL_malloc(n * size_of::<T>(), align.try_into().unwrap()) as *mut T }
+// Buffers built from Rust collections use the same 64-byte alignment as `new_align_64`.
+impl<T> FromIterator<T> for Buffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut buffer = Self::try_with_capacity(iter.size_hint().0, 64, false).unwrap();
+        for value in iter {
+            buffer.push(value);
+        }
+        buffer
+    }
 }
 
-pub fn free<T>(ptr: *const T) {
-    unsafe { sys::MKL_free(ptr as *mut c_void) };
+impl<T> From<Vec<T>> for Buffer<T> {
+    fn from(mut vec: Vec<T>) -> Self {
+        let mut buffer = Self::try_with_capacity(vec.len(), 64, false).unwrap();
+        unsafe {
+            ptr::copy_nonoverlapping(vec.as_ptr(), buffer.data, vec.len());
+            buffer.len = vec.len();
+            vec.set_len(0);
+        }
+        buffer
+    }
 }
 
-pub fn free_buffers() {
-    unsafe { sys::MKL_Free_Buffers() };
+impl<T> From<Buffer<T>> for Vec<T> {
+    fn from(mut buffer: Buffer<T>) -> Self {
+        let mut vec = Vec::with_capacity(buffer.len);
+        unsafe {
+            ptr::copy_nonoverlapping(buffer.data, vec.as_mut_ptr(), buffer.len);
+            vec.set_len(buffer.len);
+            buffer.len = 0;
+        }
+        vec
+    }
 }
 
+impl<T> IntoIterator for Buffer<T> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
 
-pub fn vml_get_mode() -> VmlMode {
-    VmlMode::try_from(unsafe { sys::vmlGetMode() }).unwrap()
+    fn into_iter(self) -> vec::IntoIter<T> {
+        Vec::from(self).into_iter()
+    }
 }
 
-pub fn vml_set_mode(new_mode: VmlMode) -> VmlMode {
-    VmlMode::try_from(unsafe { sys::vmlSetMode(new_mode.into()) }).unwrap()
+impl<'a, T> IntoIterator for &'a Buffer<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
 }
 
+impl<'a, T> IntoIterator for &'a mut Buffer<T> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
 
-pub fn vsl_new_stream(brng: VslBrng, seed: u32) -> Result<*mut VslStreamState, VslError> {
-    let mut stream: *mut VslStreamState = ptr::null_mut();
-    match unsafe { sys::vslNewStream(&mut stream as *mut *mut VslStreamState, brng.into(), seed) } {
-        sys::VSL_STATUS_OK => Ok(stream),
-        error => Err(VslError::try_from(error).unwrap()),
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
     }
 }
 
-pub fn vsl_delete_stream(stream: &mut *mut VslStreamState) -> Result<(), VslError> {
-    match unsafe { sys::vslDeleteStream(stream as *mut *mut VslStreamState) } {
-        sys::VSL_STATUS_OK => Ok(()),
-        error => Err(VslError::try_from(error).unwrap()),
+impl<T> Buffer<T> {
+    pub fn strided(&self, len: usize, stride: usize) -> Result<StridedSlice<'_, T>, StridedSliceError> {
+        StridedSlice::new(self, len, stride)
+    }
+
+    pub fn strided_mut(&mut self, len: usize, stride: usize) -> Result<StridedSliceMut<'_, T>, StridedSliceError> {
+        StridedSliceMut::new(self, len, stride)
     }
 }
 
 
-pub fn vs_rng_uniform(method: VslRngMethodUniform, stream: *mut VslStreamState, n: i32, r: *mut f32, a: f32, b: f32) -> Result<(), VslError> {
-    match unsafe { sys::vsRngUniform(method.into(), stream, n, r, a, b) } {
-        sys::VSL_STATUS_OK => Ok(()),
-        error => Err(VslError::try_from(error).unwrap()),
-    }
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StridedSliceError {
+    ZeroStride,
+    OutOfBounds { len: usize, stride: usize, available: usize },
 }
 
-pub fn vd_rng_uniform(method: VslRngMethodUniform, stream: *mut VslStreamState, n: i32, r: *mut f64, a: f64, b: f64) -> Result<(), VslError> {
-    match unsafe { sys::vdRngUniform(method.into(), stream, n, r, a, b) } {
-        sys::VSL_STATUS_OK => Ok(()),
-        error => Err(VslError::try_from(error).unwrap()),
+fn strided_span(available: usize, len: usize, stride: usize) -> Result<usize, StridedSliceError> {
+    if stride == 0 {
+        return Err(StridedSliceError::ZeroStride);
+    }
+    if len == 0 {
+        return Ok(0);
     }
-}
 
-pub fn vi_rng_uniform(method: VslRngMethodUniformDiscrete, stream: *mut VslStreamState, n: i32, r: *mut i32, a: i32, b: i32) -> Result<(), VslError> {
-    match unsafe { sys::viRngUniform(method.into(), stream, n, r, a, b) } {
-        sys::VSL_STATUS_OK => Ok(()),
-        error => Err(VslError::try_from(error).unwrap()),
+    match (len - 1).checked_mul(stride) {
+        Some(last) if last < available => Ok(last + 1),
+        _ => Err(StridedSliceError::OutOfBounds { len, stride, available }),
     }
 }
 
 
-pub struct Buffer<T> {
-    data: *mut T,
+/// A read-only view of `len` elements spaced `stride` elements apart.
+#[derive(Debug, Copy, Clone)]
+pub struct StridedSlice<'a, T> {
+    data: &'a [T],
     len: usize,
+    stride: usize,
 }
 
-impl<T> Buffer<T> {
-    pub fn new(len: usize, align: usize) -> Self {
-        Self {
-            data: malloc(len, align),
+impl<'a, T> StridedSlice<'a, T> {
+    pub fn new(data: &'a [T], len: usize, stride: usize) -> Result<Self, StridedSliceError> {
+        let span = strided_span(data.len(), len, stride)?;
+
+        Ok(Self {
+            data: &data[..span],
             len,
+            stride,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.data.as_ptr()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        match index < self.len {
+            true => Some(&self.data[index * self.stride]),
+            false => None,
         }
     }
 
-    pub fn new_align_64(len: usize) -> Self {
-        Self::new(len, 64)
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> {
+        self.data.iter().step_by(self.stride)
+    }
+}
+
+impl<'a, T> From<&'a [T]> for StridedSlice<'a, T> {
+    fn from(data: &'a [T]) -> Self {
+        Self {
+            data,
+            len: data.len(),
+            stride: 1,
+        }
+    }
+}
+
+
+/// A mutable view of `len` elements spaced `stride` elements apart.
+#[derive(Debug)]
+pub struct StridedSliceMut<'a, T> {
+    data: &'a mut [T],
+    len: usize,
+    stride: usize,
+}
+
+impl<'a, T> StridedSliceMut<'a, T> {
+    pub fn new(data: &'a mut [T], len: usize, stride: usize) -> Result<Self, StridedSliceError> {
+        let span = strided_span(data.len(), len, stride)?;
+
+        Ok(Self {
+            data: &mut data[..span],
+            len,
+            stride,
+        })
     }
 
     pub fn len(&self) -> usize {
         self.len
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
     pub fn as_ptr(&self) -> *const T {
-        self.data
+        self.data.as_ptr()
     }
 
-    pub fn as_slice(&self) -> &[T] {
-        unsafe { slice::from_raw_parts(self.data, self.len) }
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr()
     }
 
-    pub fn as_mut_ptr(&self) -> *mut T {
-        self.data
+    pub fn as_strided(&self) -> StridedSlice<'_, T> {
+        StridedSlice {
+            data: self.data,
+            len: self.len,
+            stride: self.stride,
+        }
     }
 
-    pub fn as_mut_slice(&self) -> &mut [T] {
-        unsafe { slice::from_raw_parts_mut(self.data, self.len) }
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match index < self.len {
+            true => Some(&self.data[index * self.stride]),
+            false => None,
+        }
     }
-}
 
-impl<T> Drop for Buffer<T> {
-    fn drop(&mut self) {
-        free(self.data);
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        match index < self.len {
+            true => Some(&mut self.data[index * self.stride]),
+            false => None,
+        }
     }
-}
 
-impl<T> Deref for Buffer<T> {
-    type Target = [T];
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter().step_by(self.stride)
+    }
 
-    fn deref(&self) -> &[T] {
-        self.as_slice()
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut().step_by(self.stride)
     }
 }
 
-impl<T> DerefMut for Buffer<T> {
-    fn deref_mut(&mut self) -> &mut [T] {
-        self.as_mut_slice()
+impl<'a, T> From<&'a mut [T]> for StridedSliceMut<'a, T> {
+    fn from(data: &'a mut [T]) -> Self {
+        Self {
+            len: data.len(),
+            data,
+            stride: 1,
+        }
     }
 }
 
 
+/// An MKL random stream.
+///
+/// A stream may be moved to and dropped on any thread, so it is `Send`. This holds for every
+/// state a stream can carry: the built-in BRNGs keep plain data, `UserBrng` requires `Send`, and
+/// the refill closures of abstract streams must be `Send` as well. Generating numbers
+/// updates the stream state even though the methods take `&self`, so it is not `Sync`; give each
+/// thread its own stream, e.g. from `VslStream::new_independent`.
 pub struct VslStream {
     state: *mut VslStreamState
 }
 
+unsafe impl Send for VslStream {}
+
 impl VslStream {
-    pub fn new(brng: VslBrng, seed: u32) -> Result<Self, VslError> {
+    pub fn new(brng: impl Into<StreamBrng>, seed: u32) -> Result<Self, VslError> {
         Ok(Self { state: vsl_new_stream(brng, seed)? })
     }
 
@@ -315,6 +1344,42 @@ impl VslStream {
     pub fn as_mut_ptr(&self) -> *mut VslStreamState {
         self.state
     }
+
+    // Abstract streams point into the buffer of the `AbstractStream` that owns them, so a copy or
+    // a saved state could outlive it.
+    fn is_abstract(&self) -> bool {
+        matches!(
+            unsafe { sys::vslGetStreamStateBrng(self.state) },
+            sys::VSL_BRNG_IABSTRACT | sys::VSL_BRNG_DABSTRACT | sys::VSL_BRNG_SABSTRACT
+        )
+    }
+
+    /// Copies the stream. Streams of the abstract BRNGs cannot be copied and return
+    /// `VslError::InvalidAbstractStream`.
+    pub fn try_clone(&self) -> Result<Self, VslError> {
+        if self.is_abstract() {
+            return Err(VslError::InvalidAbstractStream);
+        }
+        Ok(Self { state: vsl_copy_stream(self.state)? })
+    }
+
+    /// Overwrites the state of `self` with that of `other`, which must use the same BRNG and must
+    /// not be an abstract stream.
+    pub fn copy_state_from(&mut self, other: &VslStream) -> Result<(), VslError> {
+        if self.is_abstract() || other.is_abstract() {
+            return Err(VslError::InvalidAbstractStream);
+        }
+        vsl_copy_stream_state(self.state, other.state)
+    }
+}
+
+/// # Panics
+///
+/// Panics if `try_clone` fails, which includes streams of the abstract BRNGs.
+impl Clone for VslStream {
+    fn clone(&self) -> Self {
+        self.try_clone().unwrap()
+    }
 }
 
 impl Drop for VslStream {
@@ -325,19 +1390,19 @@ impl Drop for VslStream {
 
 
 impl Buffer<f32> {
-    pub fn rng_uniform(&self, method: VslRngMethodUniform, stream: &VslStream, a: f32, b: f32) -> Result<(), VslError> {
+    pub fn rng_uniform(&mut self, method: VslRngMethodUniform, stream: &VslStream, a: f32, b: f32) -> Result<(), VslError> {
         vs_rng_uniform(method, stream.as_mut_ptr(), self.len().try_into().unwrap(), self.as_mut_ptr(), a, b)
     }
 }
 
 impl Buffer<f64> {
-    pub fn rng_uniform(&self, method: VslRngMethodUniform, stream: &VslStream, a: f64, b: f64) -> Result<(), VslError> {
+    pub fn rng_uniform(&mut self, method: VslRngMethodUniform, stream: &VslStream, a: f64, b: f64) -> Result<(), VslError> {
         vd_rng_uniform(method, stream.as_mut_ptr(), self.len().try_into().unwrap(), self.as_mut_ptr(), a, b)
     }
 }
 
 impl Buffer<i32> {
-    pub fn rng_uniform(&self, method: VslRngMethodUniformDiscrete, stream: &VslStream, a: i32, b: i32) -> Result<(), VslError> {
+    pub fn rng_uniform(&mut self, method: VslRngMethodUniformDiscrete, stream: &VslStream, a: i32, b: i32) -> Result<(), VslError> {
         vi_rng_uniform(method, stream.as_mut_ptr(), self.len().try_into().unwrap(), self.as_mut_ptr(), a, b)
     }
 }
@@ -404,6 +1469,96 @@ mod tests {
         });
 
         assert_eq!(vml_get_mode(), new_mode);
+
+        vml_set_mode(old_mode);
+    }
+
+    #[test]
+    fn test_vml_mode_guard() {
+        let old_mode = vml_get_mode();
+        let new_mode = VmlMode {
+            accuracy: VmlAccuracyMode::EnhancedPerformance,
+            ftzdaz: VmlFtzdazMode::Off,
+            error: VmlErrorMode::Ignore,
+        };
+
+        {
+            let guard = VmlModeGuard::new(new_mode);
+
+            assert_eq!(guard.previous(), old_mode);
+            assert_eq!(vml_get_mode(), new_mode);
+        }
+
+        assert_eq!(vml_get_mode(), old_mode);
+
+        let result = std::panic::catch_unwind(|| {
+            let _guard = VmlModeGuard::new(new_mode);
+            panic!();
+        });
+
+        assert!(result.is_err());
+        assert_eq!(vml_get_mode(), old_mode);
+    }
+
+    #[test]
+    fn test_with_vml_mode() {
+        let old_mode = vml_get_mode();
+        let new_mode = VmlMode {
+            accuracy: VmlAccuracyMode::LowAccuracy,
+            ftzdaz: VmlFtzdazMode::Current,
+            error: VmlErrorMode::Default,
+        };
+
+        let mode = with_vml_mode(new_mode, vml_get_mode);
+
+        assert_eq!(mode, new_mode);
+        assert_eq!(vml_get_mode(), old_mode);
+    }
+
+    #[test]
+    fn test_vml_err_status() {
+        vml_clear_err_status();
+
+        assert_eq!(vml_get_err_status(), VmlStatus::Ok);
+        assert_eq!(vml_set_err_status(VmlStatus::Overflow), VmlStatus::Ok);
+        assert_eq!(vml_get_err_status(), VmlStatus::Overflow);
+        assert_eq!(vml_clear_err_status(), VmlStatus::Overflow);
+        assert_eq!(vml_get_err_status(), VmlStatus::Ok);
+    }
+
+    #[test]
+    fn test_vml_error_callback() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let recorded = errors.clone();
+
+        let _mode = VmlModeGuard::new(VmlMode {
+            accuracy: VmlAccuracyMode::HighAccuracy,
+            ftzdaz: VmlFtzdazMode::Current,
+            error: VmlErrorMode::Callback,
+        });
+
+        {
+            let _callback = vml_set_error_callback(move |context| {
+                recorded.borrow_mut().push((context.status(), context.index(), context.arg1(), context.function_name().to_string()));
+                context.set_result1(42.0);
+            });
+
+            let mut r = [0.0; 3];
+            vml::ln(&[-1.0, 1.0, -2.0], &mut r).unwrap_err();
+
+            assert_eq!(r, [42.0, 0.0, 42.0]);
+        }
+
+        let errors = errors.borrow();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].0, Some(VmlStatus::DomainError));
+        assert_eq!((errors[0].1, errors[0].2), (Some(0), -1.0));
+        assert_eq!((errors[1].1, errors[1].2), (Some(2), -2.0));
+        assert!(errors[0].3.contains("Ln"));
+
+        assert!(unsafe { sys::vmlGetErrorCallBack() }.is_none());
     }
 
     #[test]
@@ -419,6 +1574,43 @@ mod tests {
         free_buffers();
     }
 
+    #[test]
+    fn test_brng_id() {
+        assert_eq!(BrngId::new(VslBrng::MT2203, 6023).unwrap().index(), 6023);
+        assert_eq!(BrngId::new(VslBrng::MT2203, 6024), Err(VslError::InvalidBrngIndex));
+        assert_eq!(BrngId::new(VslBrng::WichmannHill, 273), Err(VslError::InvalidBrngIndex));
+        assert_eq!(BrngId::new(VslBrng::MT19937, 1), Err(VslError::InvalidBrngIndex));
+
+        let id = BrngId::new(VslBrng::WichmannHill, 17).unwrap();
+        assert_eq!(i32::from(id), sys::VSL_BRNG_WH + 17);
+        assert_eq!(id.brng(), VslBrng::WichmannHill);
+        assert_eq!(BrngId::try_from(i32::from(id)), Ok(id));
+        assert_eq!(BrngId::try_from(sys::VSL_BRNG_MT19937), Ok(BrngId::from(VslBrng::MT19937)));
+        assert_eq!(BrngId::try_from(sys::VSL_BRNG_MT19937 + 1), Err(VslError::InvalidBrngIndex));
+
+        let mut a: Buffer<f64> = Buffer::new_align_64(8);
+        let mut b: Buffer<f64> = Buffer::new_align_64(8);
+        let first = VslStream::new(BrngId::new(VslBrng::MT2203, 0).unwrap(), 21).unwrap();
+        let second = VslStream::new(BrngId::new(VslBrng::MT2203, 1).unwrap(), 21).unwrap();
+        a.rng_uniform(VslRngMethodUniform::Fast, &first, 0.0, 1.0).unwrap();
+        b.rng_uniform(VslRngMethodUniform::Fast, &second, 0.0, 1.0).unwrap();
+        assert_ne!(*a, *b);
+
+        free_buffers();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_brng_id_serde() {
+        let id = BrngId::new(VslBrng::MT2203, 5).unwrap();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, (sys::VSL_BRNG_MT2203 + 5).to_string());
+        assert_eq!(serde_json::from_str::<BrngId>(&json).unwrap(), id);
+
+        assert!(serde_json::from_str::<BrngId>(&(sys::VSL_BRNG_MT19937 + 1).to_string()).is_err());
+        assert!(serde_json::from_str::<BrngId>("-1").is_err());
+    }
+
     #[test]
     fn test_vd_rng_uniform() {
         let len = 8;
@@ -450,6 +1642,117 @@ mod tests {
         assert_eq!(buf.last().unwrap().clone(), 0.969321598066017);
     }
 
+    #[test]
+    fn test_buffer_constructors() {
+        let buf: Buffer<f64> = Buffer::new_align_64(4);
+        assert_eq!(*buf, [0.0; 4]);
+
+        let buf: Buffer<i32> = Buffer::zeroed(5, 64);
+        assert_eq!(*buf, [0; 5]);
+        assert_eq!(buf.as_ptr() as usize % 64, 0);
+
+        let buf = Buffer::from_elem(Complex64::new(1.0, -1.0), 3, 64);
+        assert!(buf.iter().all(|&x| x == Complex64::new(1.0, -1.0)));
+
+        let buf = Buffer::from_slice(&[1u32, 2, 3], 128);
+        assert_eq!(*buf, [1, 2, 3]);
+        assert_eq!(buf.as_ptr() as usize % 128, 0);
+
+        let mut buf = Buffer::<f32>::uninit(3, 64);
+        for (i, x) in buf.iter_mut().enumerate() {
+            x.write(i as f32);
+        }
+        let buf = unsafe { buf.assume_init() };
+        assert_eq!(*buf, [0.0, 1.0, 2.0]);
+
+        let buf = Buffer::from_elem(String::from("owned"), 2, 64);
+        assert_eq!(buf[1], "owned");
+        drop(buf);
+
+        let buf: Buffer<f64> = Buffer::new(0, 64);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_buffer_allocation_errors() {
+        assert_eq!(Buffer::<f64>::try_new(usize::MAX, 64).err(), Some(BufferError::CapacityOverflow));
+        assert_eq!(Buffer::<f64>::try_zeroed(isize::MAX as usize, 64).err(), Some(BufferError::CapacityOverflow));
+        assert_eq!(Buffer::<f64>::try_new(8, 48).err(), Some(BufferError::InvalidAlignment(48)));
+        assert_eq!(Buffer::<u8>::try_uninit(8, 1 << 40).err(), Some(BufferError::InvalidAlignment(1 << 40)));
+    }
+
+    #[test]
+    fn test_buffer_growth() {
+        let mut small: Buffer<u16> = Buffer::new(0, 64);
+        for i in 0..1000 {
+            small.push(i);
+            assert_eq!(small.as_ptr() as usize % 64, 0);
+        }
+        assert!(small.iter().copied().eq(0..1000));
+
+        let mut buf: Buffer<f64> = Buffer::new(0, 128);
+        for i in 0..100 {
+            buf.push(i as f64);
+            assert_eq!(buf.as_ptr() as usize % 128, 0);
+        }
+        assert!(buf.capacity() >= 100);
+        assert_eq!(buf[99], 99.0);
+
+        buf.extend_from_slice(&[-1.0; 50]);
+        assert_eq!(buf.len(), 150);
+        assert_eq!(buf[149], -1.0);
+        assert_eq!(buf.as_ptr() as usize % 128, 0);
+
+        buf.truncate(10);
+        assert_eq!(*buf, (0..10).map(|i| i as f64).collect::<Vec<_>>()[..]);
+        buf.resize(12, 7.0);
+        assert_eq!(buf[10..], [7.0, 7.0]);
+
+        let capacity = buf.capacity();
+        buf.reserve(capacity - buf.len());
+        assert_eq!(buf.capacity(), capacity);
+        assert_eq!(buf.try_reserve(usize::MAX), Err(BufferError::CapacityOverflow));
+
+        let mut buf = Buffer::from_elem(String::from("owned"), 2, 64);
+        buf.resize(4, String::from("more"));
+        buf.truncate(1);
+        assert_eq!(*buf, ["owned"]);
+        free_buffers();
+    }
+
+    #[test]
+    fn test_buffer_traits() {
+        let buf = Buffer::from_slice(&[1.0f64, 2.0, 3.0], 128);
+        assert_eq!(buf.alignment(), 128);
+        let copy = buf.clone();
+        assert_eq!(copy, buf);
+        assert_eq!(copy.alignment(), 128);
+        assert_eq!(copy.as_ptr() as usize % 128, 0);
+        assert_eq!(format!("{:?}", copy), "[1.0, 2.0, 3.0]");
+        assert_eq!(copy[1], 2.0);
+        assert_eq!(copy[1..], [2.0, 3.0]);
+        assert_eq!(copy.as_ref(), &[1.0, 2.0, 3.0]);
+
+        let mut buf: Buffer<i32> = (0..5).collect();
+        assert_eq!(buf.alignment(), 64);
+        for x in &mut buf {
+            *x *= 2;
+        }
+        assert_eq!(buf, vec![0, 2, 4, 6, 8]);
+        assert_eq!((&buf).into_iter().sum::<i32>(), 20);
+        assert_eq!(buf.into_iter().rev().collect::<Vec<_>>(), [8, 6, 4, 2, 0]);
+
+        let strings = vec![String::from("a"), String::from("b")];
+        let buf = Buffer::from(strings);
+        assert_eq!(buf[..], ["a", "b"]);
+        let strings: Vec<String> = buf.into();
+        assert_eq!(strings, ["a", "b"]);
+
+        assert_eq!(Buffer::<u64>::from(Vec::new()).alignment(), 64);
+        assert_eq!(Buffer::<u8>::new(4, 1).alignment(), 1);
+        free_buffers();
+    }
+
     #[test]
     fn test_vsl_stream() {
         let buf: Buffer<f64> = Buffer::new_align_64(8);
@@ -463,9 +1766,46 @@ mod tests {
         assert_eq!(buf.last().unwrap().clone(), 0.969321598066017);
     }
 
+    #[test]
+    fn test_vsl_stream_clone() {
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+        let clone = stream.clone();
+
+        let mut a: Buffer<f64> = Buffer::new_align_64(8);
+        let mut b: Buffer<f64> = Buffer::new_align_64(8);
+        a.rng_uniform(VslRngMethodUniform::Fast, &stream, 0.0, 1.0).unwrap();
+        b.rng_uniform(VslRngMethodUniform::Fast, &clone, 0.0, 1.0).unwrap();
+
+        assert_eq!(*a, *b);
+        assert_eq!(b.last().unwrap().clone(), 0.969321598066017);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_vsl_stream_copy_state_from() {
+        let stream = VslStream::new(VslBrng::MT19937, 1).unwrap();
+        let mut checkpoint = VslStream::new(VslBrng::MT19937, 2).unwrap();
+        checkpoint.copy_state_from(&stream).unwrap();
+
+        let mut a: Buffer<f64> = Buffer::new_align_64(8);
+        let mut b: Buffer<f64> = Buffer::new_align_64(8);
+        a.rng_uniform(VslRngMethodUniform::Fast, &stream, 0.0, 1.0).unwrap();
+        b.rng_uniform(VslRngMethodUniform::Fast, &checkpoint, 0.0, 1.0).unwrap();
+        assert_eq!(*a, *b);
+
+        a.rng_uniform(VslRngMethodUniform::Fast, &stream, 0.0, 1.0).unwrap();
+        assert_ne!(*a, *b);
+
+        let mut other = VslStream::new(VslBrng::Philox4x32x10, 1).unwrap();
+        assert_eq!(other.copy_state_from(&stream), Err(VslError::BrngsIncompatible));
+
+        free_buffers();
+    }
+
     #[test]
     fn test_buffer_rng_uniform() {
-        let buf: Buffer<f64> = Buffer::new_align_64(8);
+        let mut buf: Buffer<f64> = Buffer::new_align_64(8);
 
         let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
 
@@ -478,7 +1818,7 @@ mod tests {
 
     #[test]
     fn test_multiple_rng() {
-        let buf: Buffer<f64> = Buffer::new_align_64(8);
+        let mut buf: Buffer<f64> = Buffer::new_align_64(8);
 
         let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
 
@@ -490,6 +1830,80 @@ mod tests {
         assert_ne!(buf.last().unwrap().clone(), 0.969321598066017);
     }
 
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send::<VslStream>();
+        assert_send::<Buffer<f64>>();
+        assert_sync::<Buffer<f64>>();
+    }
+
+    #[test]
+    fn test_vsl_stream_threads() {
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+
+        let buf = std::thread::spawn(move || {
+            let mut buf: Buffer<f64> = Buffer::new_align_64(8);
+            buf.rng_uniform(VslRngMethodUniform::Fast, &stream, 0.0, 1.0).unwrap();
+            free_buffers();
+            buf
+        }).join().unwrap();
+
+        assert_eq!(buf.last().unwrap().clone(), 0.969321598066017);
+
+        let streams = VslStream::new_independent(VslBrng::MT2203, 21, 4, 0).unwrap();
+        let mut buffers: Vec<Buffer<f64>> = (0..4).map(|_| Buffer::new_align_64(1000)).collect();
+
+        std::thread::scope(|scope| {
+            for (stream, buf) in streams.into_iter().zip(buffers.iter_mut()) {
+                scope.spawn(move || {
+                    buf.rng_uniform(VslRngMethodUniform::Fast, &stream, 0.0, 1.0).unwrap();
+                    free_buffers();
+                });
+            }
+        });
+
+        assert!(buffers.iter().all(|buf| buf.iter().all(|x| (0.0..1.0).contains(x))));
+        assert!(buffers.windows(2).all(|w| *w[0] != *w[1]));
+
+        std::thread::scope(|scope| {
+            let shared = &buffers[0];
+            let sums: Vec<f64> = (0..2).map(|_| scope.spawn(move || shared.iter().sum::<f64>())).collect::<Vec<_>>()
+                .into_iter().map(|handle| handle.join().unwrap()).collect();
+            assert_eq!(sums[0], sums[1]);
+        });
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_strided_slice() {
+        let data = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+        let column = StridedSlice::new(&data[1..], 3, 3).unwrap();
+
+        assert_eq!(column.iter().copied().collect::<Vec<_>>(), [1, 4, 7]);
+        assert_eq!(column.get(2), Some(&7));
+        assert_eq!(column.get(3), None);
+
+        assert_eq!(StridedSlice::new(&data, 4, 3).unwrap_err(), StridedSliceError::OutOfBounds { len: 4, stride: 3, available: 9 });
+        assert_eq!(StridedSlice::new(&data, 2, 0).unwrap_err(), StridedSliceError::ZeroStride);
+        assert!(StridedSlice::new(&data[9..], 0, 5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_buffer_strided_mut() {
+        let mut buf: Buffer<i32> = Buffer::new_align_64(6);
+        buf.fill(0);
+
+        let mut view = buf.strided_mut(3, 2).unwrap();
+        view.iter_mut().for_each(|x| *x = 1);
+
+        assert_eq!(buf.as_slice(), [1, 0, 1, 0, 1, 0]);
+    }
+
     #[test]
     fn test_vsl_error() {
         let mut stream = vsl_new_stream(VslBrng::Philox4x32x10, 21).unwrap();
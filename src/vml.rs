@@ -0,0 +1,668 @@
+use std::ffi::{c_int, c_longlong};
+use paste::paste;
+
+use crate::{sys, vml_clear_err_status, vml_get_err_status, StridedSlice, StridedSliceMut, VmlMode, VmlStatus};
+
+pub mod complex;
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VmlError {
+    LengthMismatch { expected: usize, found: usize },
+    LengthOverflow(usize),
+    StrideOverflow(usize),
+    Status(VmlStatus),
+}
+
+
+/// Runs a VML call with a cleared error status and reports the status it leaves behind.
+/// `AccuracyWarning` only means MKL fell back to another accuracy, so it is not an error.
+fn vml_call(f: impl FnOnce()) -> Result<(), VmlError> {
+    vml_clear_err_status();
+    f();
+
+    match vml_get_err_status() {
+        VmlStatus::Ok | VmlStatus::AccuracyWarning => Ok(()),
+        status => Err(VmlError::Status(status)),
+    }
+}
+
+fn vml_len(len: usize) -> Result<c_int, VmlError> {
+    c_int::try_from(len).map_err(|_| VmlError::LengthOverflow(len))
+}
+
+fn vml_stride(stride: usize) -> Result<c_int, VmlError> {
+    c_int::try_from(stride).map_err(|_| VmlError::StrideOverflow(stride))
+}
+
+fn vml_mode(mode: VmlMode) -> c_longlong {
+    let mode: u32 = mode.into();
+    mode.into()
+}
+
+fn check_len(expected: usize, found: usize) -> Result<(), VmlError> {
+    match expected == found {
+        true => Ok(()),
+        false => Err(VmlError::LengthMismatch { expected, found }),
+    }
+}
+
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+
+macro_rules! vml_float_impl {
+    (
+        $t:ty, $p:ident;
+        unary: [$($unary:ident => $unary_fn:ident),*];
+        binary: [$($binary:ident => $binary_fn:ident),*]
+    ) => { paste! {
+        impl VmlFloat for $t {
+            $(
+                unsafe fn [<vml_ $unary_fn>](n: c_int, a: *const $t, r: *mut $t) {
+                    sys::[<v $p $unary>](n, a, r)
+                }
+
+                unsafe fn [<vml_ $unary_fn _mode>](n: c_int, a: *const $t, r: *mut $t, mode: c_longlong) {
+                    sys::[<v m $p $unary>](n, a, r, mode)
+                }
+
+                unsafe fn [<vml_ $unary_fn _strided>](n: c_int, a: *const $t, inca: c_int, r: *mut $t, incr: c_int) {
+                    sys::[<v $p $unary I>](n, a, inca, r, incr)
+                }
+            )*
+
+            $(
+                unsafe fn [<vml_ $binary_fn>](n: c_int, a: *const $t, b: *const $t, r: *mut $t) {
+                    sys::[<v $p $binary>](n, a, b, r)
+                }
+
+                unsafe fn [<vml_ $binary_fn _mode>](n: c_int, a: *const $t, b: *const $t, r: *mut $t, mode: c_longlong) {
+                    sys::[<v m $p $binary>](n, a, b, r, mode)
+                }
+
+                unsafe fn [<vml_ $binary_fn _strided>](n: c_int, a: *const $t, inca: c_int, b: *const $t, incb: c_int, r: *mut $t, incr: c_int) {
+                    sys::[<v $p $binary I>](n, a, inca, b, incb, r, incr)
+                }
+            )*
+
+            unsafe fn vml_powx(n: c_int, a: *const $t, b: $t, r: *mut $t) {
+                sys::[<v $p Powx>](n, a, b, r)
+            }
+
+            unsafe fn vml_powx_mode(n: c_int, a: *const $t, b: $t, r: *mut $t, mode: c_longlong) {
+                sys::[<v m $p Powx>](n, a, b, r, mode)
+            }
+
+            unsafe fn vml_powx_strided(n: c_int, a: *const $t, inca: c_int, b: $t, r: *mut $t, incr: c_int) {
+                sys::[<v $p PowxI>](n, a, inca, b, r, incr)
+            }
+
+            unsafe fn vml_sin_cos(n: c_int, a: *const $t, r1: *mut $t, r2: *mut $t) {
+                sys::[<v $p SinCos>](n, a, r1, r2)
+            }
+
+            unsafe fn vml_sin_cos_mode(n: c_int, a: *const $t, r1: *mut $t, r2: *mut $t, mode: c_longlong) {
+                sys::[<v m $p SinCos>](n, a, r1, r2, mode)
+            }
+
+            unsafe fn vml_sin_cos_strided(n: c_int, a: *const $t, inca: c_int, r1: *mut $t, incr1: c_int, r2: *mut $t, incr2: c_int) {
+                sys::[<v $p SinCosI>](n, a, inca, r1, incr1, r2, incr2)
+            }
+
+            unsafe fn vml_modf(n: c_int, a: *const $t, r1: *mut $t, r2: *mut $t) {
+                sys::[<v $p Modf>](n, a, r1, r2)
+            }
+
+            unsafe fn vml_modf_mode(n: c_int, a: *const $t, r1: *mut $t, r2: *mut $t, mode: c_longlong) {
+                sys::[<v m $p Modf>](n, a, r1, r2, mode)
+            }
+
+            unsafe fn vml_linear_frac(n: c_int, a: *const $t, b: *const $t, scale_a: $t, shift_a: $t, scale_b: $t, shift_b: $t, r: *mut $t) {
+                sys::[<v $p LinearFrac>](n, a, b, scale_a, shift_a, scale_b, shift_b, r)
+            }
+
+            unsafe fn vml_linear_frac_mode(n: c_int, a: *const $t, b: *const $t, scale_a: $t, shift_a: $t, scale_b: $t, shift_b: $t, r: *mut $t, mode: c_longlong) {
+                sys::[<v m $p LinearFrac>](n, a, b, scale_a, shift_a, scale_b, shift_b, r, mode)
+            }
+        }
+    }};
+}
+
+macro_rules! vml_float {
+    (
+        unary: [$($unary:ident => $unary_fn:ident),* $(,)?];
+        binary: [$($binary:ident => $binary_fn:ident),* $(,)?];
+    ) => { paste! {
+        /// Element types accepted by the real VML functions, i.e. `f32` (`vs*`) and `f64` (`vd*`).
+        ///
+        /// The methods are thin unchecked bindings used by the safe functions in this module and
+        /// are not meant to be called directly.
+        #[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+        pub trait VmlFloat: Copy + private::Sealed {
+            $(
+                #[doc(hidden)]
+                unsafe fn [<vml_ $unary_fn>](n: c_int, a: *const Self, r: *mut Self);
+
+                #[doc(hidden)]
+                unsafe fn [<vml_ $unary_fn _mode>](n: c_int, a: *const Self, r: *mut Self, mode: c_longlong);
+
+                #[doc(hidden)]
+                unsafe fn [<vml_ $unary_fn _strided>](n: c_int, a: *const Self, inca: c_int, r: *mut Self, incr: c_int);
+            )*
+
+            $(
+                #[doc(hidden)]
+                unsafe fn [<vml_ $binary_fn>](n: c_int, a: *const Self, b: *const Self, r: *mut Self);
+
+                #[doc(hidden)]
+                unsafe fn [<vml_ $binary_fn _mode>](n: c_int, a: *const Self, b: *const Self, r: *mut Self, mode: c_longlong);
+
+                #[doc(hidden)]
+                unsafe fn [<vml_ $binary_fn _strided>](n: c_int, a: *const Self, inca: c_int, b: *const Self, incb: c_int, r: *mut Self, incr: c_int);
+            )*
+
+            #[doc(hidden)]
+            unsafe fn vml_powx(n: c_int, a: *const Self, b: Self, r: *mut Self);
+
+            #[doc(hidden)]
+            unsafe fn vml_powx_mode(n: c_int, a: *const Self, b: Self, r: *mut Self, mode: c_longlong);
+
+            #[doc(hidden)]
+            unsafe fn vml_powx_strided(n: c_int, a: *const Self, inca: c_int, b: Self, r: *mut Self, incr: c_int);
+
+            #[doc(hidden)]
+            unsafe fn vml_sin_cos(n: c_int, a: *const Self, r1: *mut Self, r2: *mut Self);
+
+            #[doc(hidden)]
+            unsafe fn vml_sin_cos_mode(n: c_int, a: *const Self, r1: *mut Self, r2: *mut Self, mode: c_longlong);
+
+            #[doc(hidden)]
+            unsafe fn vml_sin_cos_strided(n: c_int, a: *const Self, inca: c_int, r1: *mut Self, incr1: c_int, r2: *mut Self, incr2: c_int);
+
+            #[doc(hidden)]
+            unsafe fn vml_modf(n: c_int, a: *const Self, r1: *mut Self, r2: *mut Self);
+
+            #[doc(hidden)]
+            unsafe fn vml_modf_mode(n: c_int, a: *const Self, r1: *mut Self, r2: *mut Self, mode: c_longlong);
+
+            #[doc(hidden)]
+            unsafe fn vml_linear_frac(n: c_int, a: *const Self, b: *const Self, scale_a: Self, shift_a: Self, scale_b: Self, shift_b: Self, r: *mut Self);
+
+            #[doc(hidden)]
+            unsafe fn vml_linear_frac_mode(n: c_int, a: *const Self, b: *const Self, scale_a: Self, shift_a: Self, scale_b: Self, shift_b: Self, r: *mut Self, mode: c_longlong);
+        }
+
+        vml_float_impl!(
+            f32, s;
+            unary: [$($unary => $unary_fn),*];
+            binary: [$($binary => $binary_fn),*]
+        );
+
+        vml_float_impl!(
+            f64, d;
+            unary: [$($unary => $unary_fn),*];
+            binary: [$($binary => $binary_fn),*]
+        );
+
+        $(
+            pub fn $unary_fn<T: VmlFloat>(a: &[T], r: &mut [T]) -> Result<(), VmlError> {
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $unary_fn>](n, a.as_ptr(), r.as_mut_ptr()) })
+            }
+
+            pub fn [<$unary_fn _inplace>]<T: VmlFloat>(a: &mut [T]) -> Result<(), VmlError> {
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $unary_fn>](n, p as *const T, p) })
+            }
+
+            pub fn [<$unary_fn _with_mode>]<T: VmlFloat>(a: &[T], r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $unary_fn _mode>](n, a.as_ptr(), r.as_mut_ptr(), vml_mode(mode)) })
+            }
+
+            pub fn [<$unary_fn _inplace_with_mode>]<T: VmlFloat>(a: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $unary_fn _mode>](n, p as *const T, p, vml_mode(mode)) })
+            }
+
+            pub fn [<$unary_fn _strided>]<T: VmlFloat>(a: &StridedSlice<T>, r: &mut StridedSliceMut<T>) -> Result<(), VmlError> {
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                let (inca, incr) = (vml_stride(a.stride())?, vml_stride(r.stride())?);
+                vml_call(|| unsafe { T::[<vml_ $unary_fn _strided>](n, a.as_ptr(), inca, r.as_mut_ptr(), incr) })
+            }
+
+            pub fn [<$unary_fn _strided_inplace>]<T: VmlFloat>(a: &mut StridedSliceMut<T>) -> Result<(), VmlError> {
+                let n = vml_len(a.len())?;
+                let inca = vml_stride(a.stride())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $unary_fn _strided>](n, p as *const T, inca, p, inca) })
+            }
+        )*
+
+        $(
+            pub fn $binary_fn<T: VmlFloat>(a: &[T], b: &[T], r: &mut [T]) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $binary_fn>](n, a.as_ptr(), b.as_ptr(), r.as_mut_ptr()) })
+            }
+
+            pub fn [<$binary_fn _inplace>]<T: VmlFloat>(a: &mut [T], b: &[T]) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $binary_fn>](n, p as *const T, b.as_ptr(), p) })
+            }
+
+            pub fn [<$binary_fn _with_mode>]<T: VmlFloat>(a: &[T], b: &[T], r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $binary_fn _mode>](n, a.as_ptr(), b.as_ptr(), r.as_mut_ptr(), vml_mode(mode)) })
+            }
+
+            pub fn [<$binary_fn _inplace_with_mode>]<T: VmlFloat>(a: &mut [T], b: &[T], mode: VmlMode) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $binary_fn _mode>](n, p as *const T, b.as_ptr(), p, vml_mode(mode)) })
+            }
+
+            pub fn [<$binary_fn _strided>]<T: VmlFloat>(a: &StridedSlice<T>, b: &StridedSlice<T>, r: &mut StridedSliceMut<T>) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                let (inca, incb, incr) = (vml_stride(a.stride())?, vml_stride(b.stride())?, vml_stride(r.stride())?);
+                vml_call(|| unsafe { T::[<vml_ $binary_fn _strided>](n, a.as_ptr(), inca, b.as_ptr(), incb, r.as_mut_ptr(), incr) })
+            }
+
+            pub fn [<$binary_fn _strided_inplace>]<T: VmlFloat>(a: &mut StridedSliceMut<T>, b: &StridedSlice<T>) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                let n = vml_len(a.len())?;
+                let (inca, incb) = (vml_stride(a.stride())?, vml_stride(b.stride())?);
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $binary_fn _strided>](n, p as *const T, inca, b.as_ptr(), incb, p, inca) })
+            }
+        )*
+    }};
+}
+
+vml_float! {
+    unary: [
+        Abs => abs,
+        Acos => acos,
+        Acosh => acosh,
+        Acospi => acospi,
+        Asin => asin,
+        Asinh => asinh,
+        Asinpi => asinpi,
+        Atan => atan,
+        Atanh => atanh,
+        Atanpi => atanpi,
+        Cbrt => cbrt,
+        CdfNorm => cdf_norm,
+        CdfNormInv => cdf_norm_inv,
+        Ceil => ceil,
+        Cos => cos,
+        Cosd => cosd,
+        Cosh => cosh,
+        Cospi => cospi,
+        Erf => erf,
+        ErfInv => erf_inv,
+        Erfc => erfc,
+        ErfcInv => erfc_inv,
+        Exp => exp,
+        Exp10 => exp10,
+        Exp2 => exp2,
+        ExpInt1 => exp_int1,
+        Expm1 => expm1,
+        Floor => floor,
+        Frac => frac,
+        Inv => inv,
+        InvCbrt => inv_cbrt,
+        InvSqrt => inv_sqrt,
+        LGamma => lgamma,
+        Ln => ln,
+        Log10 => log10,
+        Log1p => log1p,
+        Log2 => log2,
+        Logb => logb,
+        NearbyInt => nearby_int,
+        Pow2o3 => pow2o3,
+        Pow3o2 => pow3o2,
+        Rint => rint,
+        Round => round,
+        Sin => sin,
+        Sind => sind,
+        Sinh => sinh,
+        Sinpi => sinpi,
+        Sqr => sqr,
+        Sqrt => sqrt,
+        TGamma => tgamma,
+        Tan => tan,
+        Tand => tand,
+        Tanh => tanh,
+        Tanpi => tanpi,
+        Trunc => trunc,
+    ];
+    binary: [
+        Add => add,
+        Atan2 => atan2,
+        Atan2pi => atan2pi,
+        CopySign => copy_sign,
+        Div => div,
+        Fdim => fdim,
+        Fmax => fmax,
+        Fmin => fmin,
+        Fmod => fmod,
+        Hypot => hypot,
+        MaxMag => max_mag,
+        MinMag => min_mag,
+        Mul => mul,
+        NextAfter => next_after,
+        Pow => pow,
+        Powr => powr,
+        Remainder => remainder,
+        Sub => sub,
+    ];
+}
+
+
+pub fn powx<T: VmlFloat>(a: &[T], b: T, r: &mut [T]) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_powx(n, a.as_ptr(), b, r.as_mut_ptr()) })
+}
+
+pub fn powx_inplace<T: VmlFloat>(a: &mut [T], b: T) -> Result<(), VmlError> {
+    let n = vml_len(a.len())?;
+    let p = a.as_mut_ptr();
+    vml_call(|| unsafe { T::vml_powx(n, p as *const T, b, p) })
+}
+
+pub fn powx_with_mode<T: VmlFloat>(a: &[T], b: T, r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_powx_mode(n, a.as_ptr(), b, r.as_mut_ptr(), vml_mode(mode)) })
+}
+
+pub fn powx_inplace_with_mode<T: VmlFloat>(a: &mut [T], b: T, mode: VmlMode) -> Result<(), VmlError> {
+    let n = vml_len(a.len())?;
+    let p = a.as_mut_ptr();
+    vml_call(|| unsafe { T::vml_powx_mode(n, p as *const T, b, p, vml_mode(mode)) })
+}
+
+pub fn powx_strided<T: VmlFloat>(a: &StridedSlice<T>, b: T, r: &mut StridedSliceMut<T>) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    let (inca, incr) = (vml_stride(a.stride())?, vml_stride(r.stride())?);
+    vml_call(|| unsafe { T::vml_powx_strided(n, a.as_ptr(), inca, b, r.as_mut_ptr(), incr) })
+}
+
+pub fn powx_strided_inplace<T: VmlFloat>(a: &mut StridedSliceMut<T>, b: T) -> Result<(), VmlError> {
+    let n = vml_len(a.len())?;
+    let inca = vml_stride(a.stride())?;
+    let p = a.as_mut_ptr();
+    vml_call(|| unsafe { T::vml_powx_strided(n, p as *const T, inca, b, p, inca) })
+}
+
+pub fn sin_cos<T: VmlFloat>(a: &[T], sin: &mut [T], cos: &mut [T]) -> Result<(), VmlError> {
+    check_len(a.len(), sin.len())?;
+    check_len(a.len(), cos.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_sin_cos(n, a.as_ptr(), sin.as_mut_ptr(), cos.as_mut_ptr()) })
+}
+
+pub fn sin_cos_with_mode<T: VmlFloat>(a: &[T], sin: &mut [T], cos: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), sin.len())?;
+    check_len(a.len(), cos.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_sin_cos_mode(n, a.as_ptr(), sin.as_mut_ptr(), cos.as_mut_ptr(), vml_mode(mode)) })
+}
+
+pub fn sin_cos_strided<T: VmlFloat>(a: &StridedSlice<T>, sin: &mut StridedSliceMut<T>, cos: &mut StridedSliceMut<T>) -> Result<(), VmlError> {
+    check_len(a.len(), sin.len())?;
+    check_len(a.len(), cos.len())?;
+    let n = vml_len(a.len())?;
+    let (inca, incsin, inccos) = (vml_stride(a.stride())?, vml_stride(sin.stride())?, vml_stride(cos.stride())?);
+    vml_call(|| unsafe { T::vml_sin_cos_strided(n, a.as_ptr(), inca, sin.as_mut_ptr(), incsin, cos.as_mut_ptr(), inccos) })
+}
+
+pub fn modf<T: VmlFloat>(a: &[T], integral: &mut [T], fractional: &mut [T]) -> Result<(), VmlError> {
+    check_len(a.len(), integral.len())?;
+    check_len(a.len(), fractional.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_modf(n, a.as_ptr(), integral.as_mut_ptr(), fractional.as_mut_ptr()) })
+}
+
+pub fn modf_with_mode<T: VmlFloat>(a: &[T], integral: &mut [T], fractional: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), integral.len())?;
+    check_len(a.len(), fractional.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_modf_mode(n, a.as_ptr(), integral.as_mut_ptr(), fractional.as_mut_ptr(), vml_mode(mode)) })
+}
+
+/// Computes `(scale_a * a + shift_a) / (scale_b * b + shift_b)` elementwise.
+#[allow(clippy::too_many_arguments)]
+pub fn linear_frac<T: VmlFloat>(a: &[T], b: &[T], scale_a: T, shift_a: T, scale_b: T, shift_b: T, r: &mut [T]) -> Result<(), VmlError> {
+    check_len(a.len(), b.len())?;
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_linear_frac(n, a.as_ptr(), b.as_ptr(), scale_a, shift_a, scale_b, shift_b, r.as_mut_ptr()) })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn linear_frac_with_mode<T: VmlFloat>(a: &[T], b: &[T], scale_a: T, shift_a: T, scale_b: T, shift_b: T, r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), b.len())?;
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_linear_frac_mode(n, a.as_ptr(), b.as_ptr(), scale_a, shift_a, scale_b, shift_b, r.as_mut_ptr(), vml_mode(mode)) })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vml_get_mode, Buffer, StridedSlice, StridedSliceMut, VmlAccuracyMode, VmlErrorMode, VmlFtzdazMode};
+
+    fn mode(accuracy: VmlAccuracyMode) -> VmlMode {
+        VmlMode {
+            accuracy,
+            ftzdaz: VmlFtzdazMode::Current,
+            error: VmlErrorMode::Default,
+        }
+    }
+
+    fn max_ulps(expected: &[f64], found: &[f64]) -> u64 {
+        expected.iter().zip(found.iter())
+            .map(|(x, y)| (x.to_bits() as i64 - y.to_bits() as i64).unsigned_abs())
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_exp() {
+        let a: [f64; 4] = [0.0, 1.0, -1.0, 2.5];
+        let mut r = [0.0; 4];
+
+        exp(&a, &mut r).unwrap();
+
+        for (x, y) in a.iter().zip(r.iter()) {
+            assert!((x.exp() - y).abs() <= f64::EPSILON * x.exp());
+        }
+    }
+
+    #[test]
+    fn test_ln_f32() {
+        let a: [f32; 3] = [1.0, 2.0, 10.0];
+        let mut r = [0.0f32; 3];
+
+        ln(&a, &mut r).unwrap();
+
+        for (x, y) in a.iter().zip(r.iter()) {
+            assert!((x.ln() - y).abs() <= f32::EPSILON * 4.0);
+        }
+    }
+
+    #[test]
+    fn test_sqrt_inplace() {
+        let mut a = [1.0, 4.0, 9.0, 16.0];
+
+        sqrt_inplace(&mut a).unwrap();
+
+        assert_eq!(a, [1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_add() {
+        let a = [1.0, 2.0, 3.0];
+        let b = [10.0, 20.0, 30.0];
+        let mut r = [0.0; 3];
+
+        add(&a, &b, &mut r).unwrap();
+
+        assert_eq!(r, [11.0, 22.0, 33.0]);
+
+        mul_inplace(&mut r, &b).unwrap();
+
+        assert_eq!(r, [110.0, 440.0, 990.0]);
+    }
+
+    #[test]
+    fn test_powx_sin_cos() {
+        let a: [f64; 3] = [0.0, 1.0, 2.0];
+        let mut r = [0.0; 3];
+
+        powx(&a, 2.0, &mut r).unwrap();
+
+        assert_eq!(r, [0.0, 1.0, 4.0]);
+
+        let mut s = [0.0; 3];
+        let mut c = [0.0; 3];
+
+        sin_cos(&a, &mut s, &mut c).unwrap();
+
+        for i in 0..a.len() {
+            assert!((s[i] * s[i] + c[i] * c[i] - 1.0).abs() <= 4.0 * f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_buffer_exp() {
+        let mut a: Buffer<f64> = Buffer::new_align_64(8);
+        let mut r: Buffer<f64> = Buffer::new_align_64(8);
+
+        a.fill(1.0);
+
+        exp(&a, &mut r).unwrap();
+
+        assert!(r.iter().all(|&x| (x - std::f64::consts::E).abs() <= f64::EPSILON * 4.0));
+    }
+
+    #[test]
+    fn test_exp_accuracy_modes() {
+        let a: Vec<f64> = (0..1024).map(|i| -10.0 + 20.0 * i as f64 / 1024.0).collect();
+        let expected: Vec<f64> = a.iter().map(|x| x.exp()).collect();
+
+        let global_mode = vml_get_mode();
+
+        let mut ha = vec![0.0; a.len()];
+        let mut la = vec![0.0; a.len()];
+        let mut ep = vec![0.0; a.len()];
+
+        exp_with_mode(&a, &mut ha, mode(VmlAccuracyMode::HighAccuracy)).unwrap();
+        exp_with_mode(&a, &mut la, mode(VmlAccuracyMode::LowAccuracy)).unwrap();
+        exp_with_mode(&a, &mut ep, mode(VmlAccuracyMode::EnhancedPerformance)).unwrap();
+
+        assert_eq!(vml_get_mode(), global_mode);
+
+        // HA is documented to 1 ulp and LA to 4 ulp; EP only guarantees about half of the
+        // mantissa bits. One extra ulp accounts for the error of the std reference.
+        assert!(max_ulps(&expected, &ha) <= 2);
+        assert!(max_ulps(&expected, &la) <= 5);
+        assert!(max_ulps(&expected, &ep) <= 1 << 27);
+
+        assert_ne!(ha, la);
+        assert_ne!(ha, ep);
+        assert!(max_ulps(&expected, &ep) > max_ulps(&expected, &ha));
+    }
+
+    #[test]
+    fn test_exp_inplace_with_mode() {
+        let mut a: [f64; 4] = [0.0, 1.0, -1.0, 2.5];
+        let expected = a.map(|x| x.exp());
+
+        exp_inplace_with_mode(&mut a, mode(VmlAccuracyMode::HighAccuracy)).unwrap();
+
+        assert!(max_ulps(&expected, &a) <= 2);
+    }
+
+    #[test]
+    fn test_sqrt_strided_column() {
+        let (rows, cols) = (3, 4);
+        let mut m: Buffer<f64> = Buffer::new_align_64(rows * cols);
+        m.iter_mut().enumerate().for_each(|(i, x)| *x = (i * i) as f64);
+
+        let mut column = [0.0; 3];
+        sqrt_strided(&m.strided(rows, cols).unwrap(), &mut (&mut column[..]).into()).unwrap();
+
+        assert_eq!(column, [0.0, 4.0, 8.0]);
+
+        let mut column = m.strided_mut(rows, cols).unwrap();
+        sqrt_strided_inplace(&mut column).unwrap();
+
+        assert_eq!(m[0..cols], [0.0, 1.0, 4.0, 9.0]);
+        assert_eq!(m[cols], 4.0);
+        assert_eq!(m[2 * cols], 8.0);
+    }
+
+    #[test]
+    fn test_add_strided() {
+        let a = [1.0, -1.0, 2.0, -1.0, 3.0];
+        let b = [10.0, 20.0, 30.0];
+        let mut r = [0.0; 6];
+
+        let a = StridedSlice::new(&a, 3, 2).unwrap();
+        let mut view = StridedSliceMut::new(&mut r[1..], 3, 2).unwrap();
+
+        add_strided(&a, &b[..].into(), &mut view).unwrap();
+
+        assert_eq!(r, [0.0, 11.0, 0.0, 22.0, 0.0, 33.0]);
+
+        let mut too_short = StridedSliceMut::new(&mut r, 2, 2).unwrap();
+        assert_eq!(exp_strided(&a, &mut too_short), Err(VmlError::LengthMismatch { expected: 3, found: 2 }));
+    }
+
+    #[test]
+    fn test_status_errors() {
+        let mut r = [0.0; 1];
+
+        assert_eq!(ln(&[0.0], &mut r), Err(VmlError::Status(VmlStatus::Singularity)));
+        assert_eq!(ln(&[-1.0], &mut r), Err(VmlError::Status(VmlStatus::DomainError)));
+        assert_eq!(exp(&[1000.0], &mut r), Err(VmlError::Status(VmlStatus::Overflow)));
+        assert_eq!(exp(&[-1000.0], &mut r), Err(VmlError::Status(VmlStatus::Underflow)));
+
+        assert_eq!(exp(&[1.0], &mut r), Ok(()));
+        assert_eq!(vml_get_err_status(), VmlStatus::Ok);
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        let a = [1.0, 2.0, 3.0];
+        let mut r = [0.0; 2];
+
+        assert_eq!(exp(&a, &mut r), Err(VmlError::LengthMismatch { expected: 3, found: 2 }));
+        assert_eq!(add(&a, &a[..2], &mut [0.0; 3]), Err(VmlError::LengthMismatch { expected: 3, found: 2 }));
+    }
+}
@@ -0,0 +1,364 @@
+use std::ffi::{c_int, c_longlong};
+use paste::paste;
+
+use crate::{sys, Complex32, Complex64, VmlMode};
+use super::{check_len, private, vml_call, vml_len, vml_mode, VmlError, VmlFloat};
+
+
+impl private::Sealed for Complex32 {}
+impl private::Sealed for Complex64 {}
+
+
+macro_rules! vml_complex_impl {
+    (
+        $t:ty, $real:ty, $mkl:ty, $p:ident;
+        unary: [$($unary:ident => $unary_fn:ident),*];
+        binary: [$($binary:ident => $binary_fn:ident),*]
+    ) => { paste! {
+        impl VmlComplex for $t {
+            type Real = $real;
+
+            $(
+                unsafe fn [<vml_ $unary_fn>](n: c_int, a: *const $t, r: *mut $t) {
+                    sys::[<v $p $unary>](n, a as *const $mkl, r as *mut $mkl)
+                }
+
+                unsafe fn [<vml_ $unary_fn _mode>](n: c_int, a: *const $t, r: *mut $t, mode: c_longlong) {
+                    sys::[<v m $p $unary>](n, a as *const $mkl, r as *mut $mkl, mode)
+                }
+            )*
+
+            $(
+                unsafe fn [<vml_ $binary_fn>](n: c_int, a: *const $t, b: *const $t, r: *mut $t) {
+                    sys::[<v $p $binary>](n, a as *const $mkl, b as *const $mkl, r as *mut $mkl)
+                }
+
+                unsafe fn [<vml_ $binary_fn _mode>](n: c_int, a: *const $t, b: *const $t, r: *mut $t, mode: c_longlong) {
+                    sys::[<v m $p $binary>](n, a as *const $mkl, b as *const $mkl, r as *mut $mkl, mode)
+                }
+            )*
+
+            unsafe fn vml_powx(n: c_int, a: *const $t, b: $t, r: *mut $t) {
+                sys::[<v $p Powx>](n, a as *const $mkl, b.into(), r as *mut $mkl)
+            }
+
+            unsafe fn vml_powx_mode(n: c_int, a: *const $t, b: $t, r: *mut $t, mode: c_longlong) {
+                sys::[<v m $p Powx>](n, a as *const $mkl, b.into(), r as *mut $mkl, mode)
+            }
+
+            unsafe fn vml_abs(n: c_int, a: *const $t, r: *mut $real) {
+                sys::[<v $p Abs>](n, a as *const $mkl, r)
+            }
+
+            unsafe fn vml_abs_mode(n: c_int, a: *const $t, r: *mut $real, mode: c_longlong) {
+                sys::[<v m $p Abs>](n, a as *const $mkl, r, mode)
+            }
+
+            unsafe fn vml_arg(n: c_int, a: *const $t, r: *mut $real) {
+                sys::[<v $p Arg>](n, a as *const $mkl, r)
+            }
+
+            unsafe fn vml_arg_mode(n: c_int, a: *const $t, r: *mut $real, mode: c_longlong) {
+                sys::[<v m $p Arg>](n, a as *const $mkl, r, mode)
+            }
+
+            unsafe fn vml_cis(n: c_int, a: *const $real, r: *mut $t) {
+                sys::[<v $p CIS>](n, a, r as *mut $mkl)
+            }
+
+            unsafe fn vml_cis_mode(n: c_int, a: *const $real, r: *mut $t, mode: c_longlong) {
+                sys::[<v m $p CIS>](n, a, r as *mut $mkl, mode)
+            }
+        }
+    }};
+}
+
+macro_rules! vml_complex {
+    (
+        unary: [$($unary:ident => $unary_fn:ident),* $(,)?];
+        binary: [$($binary:ident => $binary_fn:ident),* $(,)?];
+    ) => { paste! {
+        /// Element types accepted by the complex VML functions, i.e. `Complex32` (`vc*`) and
+        /// `Complex64` (`vz*`).
+        #[allow(clippy::missing_safety_doc)]
+        pub trait VmlComplex: Copy + private::Sealed {
+            type Real: VmlFloat;
+
+            $(
+                #[doc(hidden)]
+                unsafe fn [<vml_ $unary_fn>](n: c_int, a: *const Self, r: *mut Self);
+
+                #[doc(hidden)]
+                unsafe fn [<vml_ $unary_fn _mode>](n: c_int, a: *const Self, r: *mut Self, mode: c_longlong);
+            )*
+
+            $(
+                #[doc(hidden)]
+                unsafe fn [<vml_ $binary_fn>](n: c_int, a: *const Self, b: *const Self, r: *mut Self);
+
+                #[doc(hidden)]
+                unsafe fn [<vml_ $binary_fn _mode>](n: c_int, a: *const Self, b: *const Self, r: *mut Self, mode: c_longlong);
+            )*
+
+            #[doc(hidden)]
+            unsafe fn vml_powx(n: c_int, a: *const Self, b: Self, r: *mut Self);
+
+            #[doc(hidden)]
+            unsafe fn vml_powx_mode(n: c_int, a: *const Self, b: Self, r: *mut Self, mode: c_longlong);
+
+            #[doc(hidden)]
+            unsafe fn vml_abs(n: c_int, a: *const Self, r: *mut Self::Real);
+
+            #[doc(hidden)]
+            unsafe fn vml_abs_mode(n: c_int, a: *const Self, r: *mut Self::Real, mode: c_longlong);
+
+            #[doc(hidden)]
+            unsafe fn vml_arg(n: c_int, a: *const Self, r: *mut Self::Real);
+
+            #[doc(hidden)]
+            unsafe fn vml_arg_mode(n: c_int, a: *const Self, r: *mut Self::Real, mode: c_longlong);
+
+            #[doc(hidden)]
+            unsafe fn vml_cis(n: c_int, a: *const Self::Real, r: *mut Self);
+
+            #[doc(hidden)]
+            unsafe fn vml_cis_mode(n: c_int, a: *const Self::Real, r: *mut Self, mode: c_longlong);
+        }
+
+        vml_complex_impl!(
+            Complex32, f32, sys::MKL_Complex8, c;
+            unary: [$($unary => $unary_fn),*];
+            binary: [$($binary => $binary_fn),*]
+        );
+
+        vml_complex_impl!(
+            Complex64, f64, sys::MKL_Complex16, z;
+            unary: [$($unary => $unary_fn),*];
+            binary: [$($binary => $binary_fn),*]
+        );
+
+        $(
+            pub fn $unary_fn<T: VmlComplex>(a: &[T], r: &mut [T]) -> Result<(), VmlError> {
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $unary_fn>](n, a.as_ptr(), r.as_mut_ptr()) })
+            }
+
+            pub fn [<$unary_fn _inplace>]<T: VmlComplex>(a: &mut [T]) -> Result<(), VmlError> {
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $unary_fn>](n, p as *const T, p) })
+            }
+
+            pub fn [<$unary_fn _with_mode>]<T: VmlComplex>(a: &[T], r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $unary_fn _mode>](n, a.as_ptr(), r.as_mut_ptr(), vml_mode(mode)) })
+            }
+
+            pub fn [<$unary_fn _inplace_with_mode>]<T: VmlComplex>(a: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $unary_fn _mode>](n, p as *const T, p, vml_mode(mode)) })
+            }
+        )*
+
+        $(
+            pub fn $binary_fn<T: VmlComplex>(a: &[T], b: &[T], r: &mut [T]) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $binary_fn>](n, a.as_ptr(), b.as_ptr(), r.as_mut_ptr()) })
+            }
+
+            pub fn [<$binary_fn _inplace>]<T: VmlComplex>(a: &mut [T], b: &[T]) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $binary_fn>](n, p as *const T, b.as_ptr(), p) })
+            }
+
+            pub fn [<$binary_fn _with_mode>]<T: VmlComplex>(a: &[T], b: &[T], r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                check_len(a.len(), r.len())?;
+                let n = vml_len(a.len())?;
+                vml_call(|| unsafe { T::[<vml_ $binary_fn _mode>](n, a.as_ptr(), b.as_ptr(), r.as_mut_ptr(), vml_mode(mode)) })
+            }
+
+            pub fn [<$binary_fn _inplace_with_mode>]<T: VmlComplex>(a: &mut [T], b: &[T], mode: VmlMode) -> Result<(), VmlError> {
+                check_len(a.len(), b.len())?;
+                let n = vml_len(a.len())?;
+                let p = a.as_mut_ptr();
+                vml_call(|| unsafe { T::[<vml_ $binary_fn _mode>](n, p as *const T, b.as_ptr(), p, vml_mode(mode)) })
+            }
+        )*
+    }};
+}
+
+vml_complex! {
+    unary: [
+        Acos => acos,
+        Acosh => acosh,
+        Asin => asin,
+        Asinh => asinh,
+        Atan => atan,
+        Atanh => atanh,
+        Conj => conj,
+        Cos => cos,
+        Cosh => cosh,
+        Exp => exp,
+        Ln => ln,
+        Log10 => log10,
+        Sin => sin,
+        Sinh => sinh,
+        Sqrt => sqrt,
+        Tan => tan,
+        Tanh => tanh,
+    ];
+    binary: [
+        Add => add,
+        Div => div,
+        Mul => mul,
+        MulByConj => mul_by_conj,
+        Pow => pow,
+        Sub => sub,
+    ];
+}
+
+
+pub fn powx<T: VmlComplex>(a: &[T], b: T, r: &mut [T]) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_powx(n, a.as_ptr(), b, r.as_mut_ptr()) })
+}
+
+pub fn powx_inplace<T: VmlComplex>(a: &mut [T], b: T) -> Result<(), VmlError> {
+    let n = vml_len(a.len())?;
+    let p = a.as_mut_ptr();
+    vml_call(|| unsafe { T::vml_powx(n, p as *const T, b, p) })
+}
+
+pub fn powx_with_mode<T: VmlComplex>(a: &[T], b: T, r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_powx_mode(n, a.as_ptr(), b, r.as_mut_ptr(), vml_mode(mode)) })
+}
+
+pub fn powx_inplace_with_mode<T: VmlComplex>(a: &mut [T], b: T, mode: VmlMode) -> Result<(), VmlError> {
+    let n = vml_len(a.len())?;
+    let p = a.as_mut_ptr();
+    vml_call(|| unsafe { T::vml_powx_mode(n, p as *const T, b, p, vml_mode(mode)) })
+}
+
+pub fn abs<T: VmlComplex>(a: &[T], r: &mut [T::Real]) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_abs(n, a.as_ptr(), r.as_mut_ptr()) })
+}
+
+pub fn abs_with_mode<T: VmlComplex>(a: &[T], r: &mut [T::Real], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_abs_mode(n, a.as_ptr(), r.as_mut_ptr(), vml_mode(mode)) })
+}
+
+pub fn arg<T: VmlComplex>(a: &[T], r: &mut [T::Real]) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_arg(n, a.as_ptr(), r.as_mut_ptr()) })
+}
+
+pub fn arg_with_mode<T: VmlComplex>(a: &[T], r: &mut [T::Real], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_arg_mode(n, a.as_ptr(), r.as_mut_ptr(), vml_mode(mode)) })
+}
+
+/// Computes `cos(a) + i * sin(a)` elementwise.
+pub fn cis<T: VmlComplex>(a: &[T::Real], r: &mut [T]) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_cis(n, a.as_ptr(), r.as_mut_ptr()) })
+}
+
+pub fn cis_with_mode<T: VmlComplex>(a: &[T::Real], r: &mut [T], mode: VmlMode) -> Result<(), VmlError> {
+    check_len(a.len(), r.len())?;
+    let n = vml_len(a.len())?;
+    vml_call(|| unsafe { T::vml_cis_mode(n, a.as_ptr(), r.as_mut_ptr(), vml_mode(mode)) })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Complex64, b: Complex64) {
+        assert!((a.re - b.re).abs() <= 1e-12 && (a.im - b.im).abs() <= 1e-12, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_mul_by_conj() {
+        let a = [Complex64::new(1.0, 2.0), Complex64::new(0.0, 1.0)];
+        let b = [Complex64::new(3.0, -1.0), Complex64::new(0.0, 1.0)];
+        let mut r = [Complex64::default(); 2];
+
+        mul_by_conj(&a, &b, &mut r).unwrap();
+
+        assert_eq!(r, [Complex64::new(1.0, 7.0), Complex64::new(1.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_exp() {
+        let a = [Complex64::new(0.0, std::f64::consts::PI), Complex64::new(1.0, 0.0)];
+        let mut r = [Complex64::default(); 2];
+
+        exp(&a, &mut r).unwrap();
+
+        assert_close(r[0], Complex64::new(-1.0, 0.0));
+        assert_close(r[1], Complex64::new(std::f64::consts::E, 0.0));
+    }
+
+    #[test]
+    fn test_abs_arg() {
+        let a = [Complex32::new(3.0, 4.0), Complex32::new(0.0, -2.0)];
+        let mut abs_r = [0.0f32; 2];
+        let mut arg_r = [0.0f32; 2];
+
+        abs(&a, &mut abs_r).unwrap();
+        arg(&a, &mut arg_r).unwrap();
+
+        assert_eq!(abs_r, [5.0, 2.0]);
+        assert!((arg_r[0] - 4.0f32.atan2(3.0)).abs() <= f32::EPSILON);
+        assert!((arg_r[1] + std::f32::consts::FRAC_PI_2).abs() <= f32::EPSILON);
+    }
+
+    #[test]
+    fn test_cis() {
+        let a = [0.0, std::f64::consts::FRAC_PI_2];
+        let mut r = [Complex64::default(); 2];
+
+        cis(&a, &mut r).unwrap();
+
+        assert_close(r[0], Complex64::new(1.0, 0.0));
+        assert_close(r[1], Complex64::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_conj_inplace() {
+        let mut a = [Complex32::new(1.0, 2.0), Complex32::new(-3.0, -4.0)];
+
+        conj_inplace(&mut a).unwrap();
+
+        assert_eq!(a, [Complex32::new(1.0, -2.0), Complex32::new(-3.0, 4.0)]);
+    }
+
+    #[cfg(feature = "num-complex")]
+    #[test]
+    fn test_num_complex_conversion() {
+        let a = num_complex::Complex64::new(1.0, -1.0);
+        let b: Complex64 = a.into();
+
+        assert_eq!(b, Complex64::new(1.0, -1.0));
+        assert_eq!(num_complex::Complex64::from(b), a);
+    }
+}
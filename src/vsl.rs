@@ -0,0 +1,1081 @@
+use std::{ffi::{c_char, c_int, CString}, path::Path};
+use paste::paste;
+
+use crate::{
+    sys, BrngId, Buffer, StreamBrng, VslBrng, VslError, VslStream, VslRngMethodBernoulli, VslRngMethodBeta, VslRngMethodBinomial,
+    VslRngMethodCauchy, VslRngMethodChiSquare, VslRngMethodExponential, VslRngMethodGamma, VslRngMethodGaussian,
+    VslRngMethodGaussianMv, VslMatrixStorage,
+    VslRngMethodGeometric, VslRngMethodGumbel, VslRngMethodHypergeometric, VslRngMethodLaplace,
+    VslRngMethodLognormal, VslRngMethodMultinomial, VslRngMethodNegBinomial, VslRngMethodPoisson,
+    VslRngMethodPoissonV, VslRngMethodRayleigh, VslRngMethodUniform, VslRngMethodUniformBits,
+    VslRngMethodUniformBits32, VslRngMethodUniformBits64, VslRngMethodWeibull,
+};
+
+mod abstract_stream;
+mod brng;
+mod quasi;
+#[cfg(feature = "rand")]
+mod rng;
+mod seed;
+mod user_brng;
+
+pub use abstract_stream::{AbstractElement, AbstractStream};
+pub use brng::{registered_brng_count, BrngProperties};
+pub use quasi::{JoeKuoDimension, QuasiRandomStream, QuasiRandomStreamBuilder};
+#[cfg(feature = "rand")]
+pub use rng::VslRng;
+pub use seed::VslSeed;
+pub use user_brng::{register_brng, UserBrng};
+
+
+pub(crate) fn vsl_len(len: usize) -> Result<c_int, VslError> {
+    c_int::try_from(len).map_err(|_| VslError::BadArguments)
+}
+
+pub(crate) fn vsl_check(valid: bool) -> Result<(), VslError> {
+    match valid {
+        true => Ok(()),
+        false => Err(VslError::BadArguments),
+    }
+}
+
+pub(crate) fn vsl_status(status: c_int) -> Result<(), VslError> {
+    match status {
+        sys::VSL_STATUS_OK => Ok(()),
+        error => Err(VslError::try_from(error).unwrap()),
+    }
+}
+
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+
+macro_rules! vsl_float_impl {
+    ($t:ty, $p:ident) => { paste! {
+        impl VslFloat for $t {
+            const ONE: $t = 1.0;
+
+            unsafe fn vsl_uniform(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, b: $t) -> c_int {
+                sys::[<v $p RngUniform>](method, stream, n, r, a, b)
+            }
+
+            unsafe fn vsl_gaussian(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, sigma: $t) -> c_int {
+                sys::[<v $p RngGaussian>](method, stream, n, r, a, sigma)
+            }
+
+            unsafe fn vsl_exponential(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngExponential>](method, stream, n, r, a, beta)
+            }
+
+            unsafe fn vsl_laplace(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngLaplace>](method, stream, n, r, a, beta)
+            }
+
+            unsafe fn vsl_weibull(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, alpha: $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngWeibull>](method, stream, n, r, alpha, a, beta)
+            }
+
+            unsafe fn vsl_cauchy(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngCauchy>](method, stream, n, r, a, beta)
+            }
+
+            unsafe fn vsl_rayleigh(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngRayleigh>](method, stream, n, r, a, beta)
+            }
+
+            unsafe fn vsl_lognormal(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, sigma: $t, b: $t, beta: $t) -> c_int {
+                sys::[<v $p RngLognormal>](method, stream, n, r, a, sigma, b, beta)
+            }
+
+            unsafe fn vsl_gumbel(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngGumbel>](method, stream, n, r, a, beta)
+            }
+
+            unsafe fn vsl_gamma(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, alpha: $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngGamma>](method, stream, n, r, alpha, a, beta)
+            }
+
+            unsafe fn vsl_beta(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, p: $t, q: $t, a: $t, beta: $t) -> c_int {
+                sys::[<v $p RngBeta>](method, stream, n, r, p, q, a, beta)
+            }
+
+            unsafe fn vsl_chi_square(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, v: c_int) -> c_int {
+                sys::[<v $p RngChiSquare>](method, stream, n, r, v)
+            }
+
+            unsafe fn vsl_gaussian_mv(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut $t, dimen: c_int, mstorage: c_int, a: *const $t, t: *const $t) -> c_int {
+                sys::[<v $p RngGaussianMV>](method, stream, n, r, dimen, mstorage, a, t)
+            }
+        }
+    }};
+}
+
+/// Element types produced by the continuous VSL distributions, i.e. `f32` (`vs*`) and `f64` (`vd*`).
+///
+/// The methods are thin unchecked bindings used by the safe `VslStream` methods and are not meant
+/// to be called directly.
+#[allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+pub trait VslFloat: Copy + Default + PartialOrd + private::Sealed {
+    #[doc(hidden)]
+    const ONE: Self;
+
+    #[doc(hidden)]
+    unsafe fn vsl_uniform(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, b: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_gaussian(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, sigma: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_exponential(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_laplace(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_weibull(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, alpha: Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_cauchy(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_rayleigh(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_lognormal(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, sigma: Self, b: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_gumbel(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_gamma(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, alpha: Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_beta(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, p: Self, q: Self, a: Self, beta: Self) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_chi_square(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, v: c_int) -> c_int;
+
+    #[doc(hidden)]
+    unsafe fn vsl_gaussian_mv(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, r: *mut Self, dimen: c_int, mstorage: c_int, a: *const Self, t: *const Self) -> c_int;
+}
+
+vsl_float_impl!(f32, s);
+vsl_float_impl!(f64, d);
+
+
+fn positive<T: VslFloat>(x: T) -> bool {
+    x > T::default()
+}
+
+
+impl VslStream {
+    pub fn uniform<T: VslFloat>(&self, method: VslRngMethodUniform, r: &mut [T], a: T, b: T) -> Result<(), VslError> {
+        vsl_check(a < b)?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_uniform(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, b) })
+    }
+
+    pub fn gaussian<T: VslFloat>(&self, method: VslRngMethodGaussian, r: &mut [T], a: T, sigma: T) -> Result<(), VslError> {
+        vsl_check(positive(sigma))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_gaussian(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, sigma) })
+    }
+
+    pub fn exponential<T: VslFloat>(&self, method: VslRngMethodExponential, r: &mut [T], a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_exponential(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, beta) })
+    }
+
+    pub fn laplace<T: VslFloat>(&self, method: VslRngMethodLaplace, r: &mut [T], a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_laplace(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, beta) })
+    }
+
+    pub fn weibull<T: VslFloat>(&self, method: VslRngMethodWeibull, r: &mut [T], alpha: T, a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(alpha) && positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_weibull(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), alpha, a, beta) })
+    }
+
+    pub fn cauchy<T: VslFloat>(&self, method: VslRngMethodCauchy, r: &mut [T], a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_cauchy(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, beta) })
+    }
+
+    pub fn rayleigh<T: VslFloat>(&self, method: VslRngMethodRayleigh, r: &mut [T], a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_rayleigh(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, beta) })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn lognormal<T: VslFloat>(&self, method: VslRngMethodLognormal, r: &mut [T], a: T, sigma: T, b: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(sigma) && positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_lognormal(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, sigma, b, beta) })
+    }
+
+    pub fn gumbel<T: VslFloat>(&self, method: VslRngMethodGumbel, r: &mut [T], a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_gumbel(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, beta) })
+    }
+
+    pub fn gamma<T: VslFloat>(&self, method: VslRngMethodGamma, r: &mut [T], alpha: T, a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(alpha) && positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_gamma(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), alpha, a, beta) })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn beta<T: VslFloat>(&self, method: VslRngMethodBeta, r: &mut [T], p: T, q: T, a: T, beta: T) -> Result<(), VslError> {
+        vsl_check(positive(p) && positive(q) && positive(beta))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_beta(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), p, q, a, beta) })
+    }
+
+    pub fn chi_square<T: VslFloat>(&self, method: VslRngMethodChiSquare, r: &mut [T], v: i32) -> Result<(), VslError> {
+        vsl_check(v > 0)?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { T::vsl_chi_square(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), v) })
+    }
+}
+
+
+impl<T: VslFloat> Buffer<T> {
+    pub fn rng_gaussian(&mut self, method: VslRngMethodGaussian, stream: &VslStream, a: T, sigma: T) -> Result<(), VslError> {
+        stream.gaussian(method, self, a, sigma)
+    }
+
+    pub fn rng_exponential(&mut self, method: VslRngMethodExponential, stream: &VslStream, a: T, beta: T) -> Result<(), VslError> {
+        stream.exponential(method, self, a, beta)
+    }
+
+    pub fn rng_laplace(&mut self, method: VslRngMethodLaplace, stream: &VslStream, a: T, beta: T) -> Result<(), VslError> {
+        stream.laplace(method, self, a, beta)
+    }
+
+    pub fn rng_weibull(&mut self, method: VslRngMethodWeibull, stream: &VslStream, alpha: T, a: T, beta: T) -> Result<(), VslError> {
+        stream.weibull(method, self, alpha, a, beta)
+    }
+
+    pub fn rng_cauchy(&mut self, method: VslRngMethodCauchy, stream: &VslStream, a: T, beta: T) -> Result<(), VslError> {
+        stream.cauchy(method, self, a, beta)
+    }
+
+    pub fn rng_rayleigh(&mut self, method: VslRngMethodRayleigh, stream: &VslStream, a: T, beta: T) -> Result<(), VslError> {
+        stream.rayleigh(method, self, a, beta)
+    }
+
+    pub fn rng_lognormal(&mut self, method: VslRngMethodLognormal, stream: &VslStream, a: T, sigma: T, b: T, beta: T) -> Result<(), VslError> {
+        stream.lognormal(method, self, a, sigma, b, beta)
+    }
+
+    pub fn rng_gumbel(&mut self, method: VslRngMethodGumbel, stream: &VslStream, a: T, beta: T) -> Result<(), VslError> {
+        stream.gumbel(method, self, a, beta)
+    }
+
+    pub fn rng_gamma(&mut self, method: VslRngMethodGamma, stream: &VslStream, alpha: T, a: T, beta: T) -> Result<(), VslError> {
+        stream.gamma(method, self, alpha, a, beta)
+    }
+
+    pub fn rng_beta(&mut self, method: VslRngMethodBeta, stream: &VslStream, p: T, q: T, a: T, beta: T) -> Result<(), VslError> {
+        stream.beta(method, self, p, q, a, beta)
+    }
+
+    pub fn rng_chi_square(&mut self, method: VslRngMethodChiSquare, stream: &VslStream, v: i32) -> Result<(), VslError> {
+        stream.chi_square(method, self, v)
+    }
+}
+
+
+fn probability(p: f64) -> bool {
+    (0.0..=1.0).contains(&p)
+}
+
+impl VslStream {
+    pub fn uniform_bits(&self, method: VslRngMethodUniformBits, r: &mut [u32]) -> Result<(), VslError> {
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngUniformBits(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr()) })
+    }
+
+    pub fn uniform_bits32(&self, method: VslRngMethodUniformBits32, r: &mut [u32]) -> Result<(), VslError> {
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngUniformBits32(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr()) })
+    }
+
+    pub fn uniform_bits64(&self, method: VslRngMethodUniformBits64, r: &mut [u64]) -> Result<(), VslError> {
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngUniformBits64(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr()) })
+    }
+
+    pub fn bernoulli(&self, method: VslRngMethodBernoulli, r: &mut [i32], p: f64) -> Result<(), VslError> {
+        vsl_check(probability(p))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngBernoulli(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), p) })
+    }
+
+    pub fn geometric(&self, method: VslRngMethodGeometric, r: &mut [i32], p: f64) -> Result<(), VslError> {
+        vsl_check(p > 0.0 && p < 1.0)?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngGeometric(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), p) })
+    }
+
+    pub fn binomial(&self, method: VslRngMethodBinomial, r: &mut [i32], ntrial: i32, p: f64) -> Result<(), VslError> {
+        vsl_check(ntrial >= 0 && probability(p))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngBinomial(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), ntrial, p) })
+    }
+
+    /// Draws the number of marked elements in samples of size `s` taken without replacement from a
+    /// lot of `l` elements, `m` of which are marked.
+    pub fn hypergeometric(&self, method: VslRngMethodHypergeometric, r: &mut [i32], l: i32, s: i32, m: i32) -> Result<(), VslError> {
+        vsl_check(l >= 0 && (0..=l).contains(&s) && (0..=l).contains(&m))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngHypergeometric(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), l, s, m) })
+    }
+
+    pub fn poisson(&self, method: VslRngMethodPoisson, r: &mut [i32], lambda: f64) -> Result<(), VslError> {
+        vsl_check(lambda > 0.0)?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngPoisson(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), lambda) })
+    }
+
+    /// Draws `r[i]` from a Poisson distribution with mean `lambda[i]`.
+    pub fn poisson_v(&self, method: VslRngMethodPoissonV, r: &mut [i32], lambda: &[f64]) -> Result<(), VslError> {
+        vsl_check(r.len() == lambda.len() && lambda.iter().all(|&l| l > 0.0))?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngPoissonV(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), lambda.as_ptr()) })
+    }
+
+    pub fn neg_binomial(&self, method: VslRngMethodNegBinomial, r: &mut [i32], a: f64, p: f64) -> Result<(), VslError> {
+        vsl_check(a > 0.0 && p > 0.0 && p < 1.0)?;
+        let n = vsl_len(r.len())?;
+        vsl_status(unsafe { sys::viRngNegBinomial(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), a, p) })
+    }
+
+    /// Fills `r` with `r.len() / p.len()` row-major vectors of per-outcome counts for `ntrial`
+    /// trials with outcome probabilities `p`.
+    pub fn multinomial(&self, method: VslRngMethodMultinomial, r: &mut [i32], ntrial: i32, p: &[f64]) -> Result<(), VslError> {
+        vsl_check(ntrial >= 0 && !p.is_empty() && r.len().is_multiple_of(p.len()) && p.iter().all(|&p| probability(p)))?;
+        let n = vsl_len(r.len() / p.len())?;
+        let k = vsl_len(p.len())?;
+        vsl_status(unsafe { sys::viRngMultinomial(method.into(), self.as_mut_ptr(), n, r.as_mut_ptr(), ntrial, k, p.as_ptr()) })
+    }
+}
+
+
+impl Buffer<u32> {
+    pub fn rng_uniform_bits(&mut self, method: VslRngMethodUniformBits, stream: &VslStream) -> Result<(), VslError> {
+        stream.uniform_bits(method, self)
+    }
+
+    pub fn rng_uniform_bits32(&mut self, method: VslRngMethodUniformBits32, stream: &VslStream) -> Result<(), VslError> {
+        stream.uniform_bits32(method, self)
+    }
+}
+
+impl Buffer<u64> {
+    pub fn rng_uniform_bits64(&mut self, method: VslRngMethodUniformBits64, stream: &VslStream) -> Result<(), VslError> {
+        stream.uniform_bits64(method, self)
+    }
+}
+
+impl Buffer<i32> {
+    pub fn rng_bernoulli(&mut self, method: VslRngMethodBernoulli, stream: &VslStream, p: f64) -> Result<(), VslError> {
+        stream.bernoulli(method, self, p)
+    }
+
+    pub fn rng_geometric(&mut self, method: VslRngMethodGeometric, stream: &VslStream, p: f64) -> Result<(), VslError> {
+        stream.geometric(method, self, p)
+    }
+
+    pub fn rng_binomial(&mut self, method: VslRngMethodBinomial, stream: &VslStream, ntrial: i32, p: f64) -> Result<(), VslError> {
+        stream.binomial(method, self, ntrial, p)
+    }
+
+    pub fn rng_hypergeometric(&mut self, method: VslRngMethodHypergeometric, stream: &VslStream, l: i32, s: i32, m: i32) -> Result<(), VslError> {
+        stream.hypergeometric(method, self, l, s, m)
+    }
+
+    pub fn rng_poisson(&mut self, method: VslRngMethodPoisson, stream: &VslStream, lambda: f64) -> Result<(), VslError> {
+        stream.poisson(method, self, lambda)
+    }
+
+    pub fn rng_poisson_v(&mut self, method: VslRngMethodPoissonV, stream: &VslStream, lambda: &[f64]) -> Result<(), VslError> {
+        stream.poisson_v(method, self, lambda)
+    }
+
+    pub fn rng_neg_binomial(&mut self, method: VslRngMethodNegBinomial, stream: &VslStream, a: f64, p: f64) -> Result<(), VslError> {
+        stream.neg_binomial(method, self, a, p)
+    }
+
+    pub fn rng_multinomial(&mut self, method: VslRngMethodMultinomial, stream: &VslStream, ntrial: i32, p: &[f64]) -> Result<(), VslError> {
+        stream.multinomial(method, self, ntrial, p)
+    }
+}
+
+
+/// Cholesky factor of a covariance matrix in one of the `VSL_MATRIX_STORAGE_*` layouts.
+///
+/// `Full` and `Packed` hold the upper-triangular `U` with `Σ = Uᵀ U`, row by row: `Full` stores all
+/// `dimen * dimen` elements (the strictly lower part is ignored) and `Packed` only the
+/// `dimen * (dimen + 1) / 2` elements on and above the diagonal. `Diagonal` stores the `dimen`
+/// standard deviations of a diagonal covariance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CholeskyFactor<T> {
+    storage: VslMatrixStorage,
+    dimen: usize,
+    data: Vec<T>,
+}
+
+impl<T: VslFloat> CholeskyFactor<T> {
+    pub fn new(storage: VslMatrixStorage, dimen: usize, data: Vec<T>) -> Result<Self, VslError> {
+        let expected = match storage {
+            VslMatrixStorage::Full => dimen.checked_mul(dimen),
+            VslMatrixStorage::Packed => dimen.checked_add(1).and_then(|d| dimen.checked_mul(d)).map(|n| n / 2),
+            VslMatrixStorage::Diagonal => Some(dimen),
+        };
+        vsl_check(dimen > 0 && expected == Some(data.len()))?;
+        vsl_len(dimen)?;
+        Ok(Self { storage, dimen, data })
+    }
+
+    pub fn full(dimen: usize, data: Vec<T>) -> Result<Self, VslError> {
+        Self::new(VslMatrixStorage::Full, dimen, data)
+    }
+
+    pub fn packed(dimen: usize, data: Vec<T>) -> Result<Self, VslError> {
+        Self::new(VslMatrixStorage::Packed, dimen, data)
+    }
+
+    pub fn diagonal(data: Vec<T>) -> Result<Self, VslError> {
+        Self::new(VslMatrixStorage::Diagonal, data.len(), data)
+    }
+
+    pub fn storage(&self) -> VslMatrixStorage {
+        self.storage
+    }
+
+    pub fn dimen(&self) -> usize {
+        self.dimen
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianMv<T> {
+    mean: Vec<T>,
+    factor: CholeskyFactor<T>,
+}
+
+impl<T: VslFloat> GaussianMv<T> {
+    pub fn new(mean: Vec<T>, factor: CholeskyFactor<T>) -> Result<Self, VslError> {
+        vsl_check(mean.len() == factor.dimen())?;
+        Ok(Self { mean, factor })
+    }
+
+    pub fn dimen(&self) -> usize {
+        self.factor.dimen()
+    }
+
+    pub fn mean(&self) -> &[T] {
+        &self.mean
+    }
+
+    pub fn factor(&self) -> &CholeskyFactor<T> {
+        &self.factor
+    }
+
+    /// Fills `r` with `r.len() / self.dimen()` draws, one per row.
+    pub fn sample(&self, method: VslRngMethodGaussianMv, stream: &VslStream, r: &mut [T]) -> Result<(), VslError> {
+        let dimen = self.dimen();
+        vsl_check(r.len().is_multiple_of(dimen))?;
+        let n = vsl_len(r.len() / dimen)?;
+        let storage = u32::from(self.factor.storage()) as c_int;
+        vsl_status(unsafe {
+            T::vsl_gaussian_mv(
+                method.into(), stream.as_mut_ptr(), n, r.as_mut_ptr(), vsl_len(dimen)?, storage,
+                self.mean.as_ptr(), self.factor.as_slice().as_ptr(),
+            )
+        })
+    }
+}
+
+impl VslStream {
+    pub fn gaussian_mv<T: VslFloat>(&self, method: VslRngMethodGaussianMv, r: &mut [T], dist: &GaussianMv<T>) -> Result<(), VslError> {
+        dist.sample(method, self, r)
+    }
+}
+
+impl<T: VslFloat> Buffer<T> {
+    pub fn rng_gaussian_mv(&mut self, method: VslRngMethodGaussianMv, stream: &VslStream, dist: &GaussianMv<T>) -> Result<(), VslError> {
+        dist.sample(method, stream, self)
+    }
+}
+
+
+impl VslStream {
+    /// Makes this stream produce every `nstreams`-th number of the original sequence, starting at
+    /// the `k`-th.
+    pub fn leapfrog(&mut self, k: usize, nstreams: usize) -> Result<(), VslError> {
+        vsl_check(k < nstreams)?;
+        vsl_status(unsafe { sys::vslLeapfrogStream(self.state, vsl_len(k)?, vsl_len(nstreams)?) })
+    }
+
+    pub fn skip_ahead(&mut self, n: u64) -> Result<(), VslError> {
+        match i64::try_from(n) {
+            Ok(n) => vsl_status(unsafe { sys::vslSkipAheadStream(self.state, n) }),
+            Err(_) => self.skip_ahead_ex(n.into()),
+        }
+    }
+
+    pub fn skip_ahead_ex(&mut self, n: u128) -> Result<(), VslError> {
+        let nskip = [n as u64, (n >> 64) as u64];
+        vsl_status(unsafe { sys::vslSkipAheadStreamEx(self.state, 2, nskip.as_ptr()) })
+    }
+
+    /// Creates `count` non-overlapping streams for `brng`.
+    ///
+    /// MT2203 and Wichmann-Hill use separate members of their BRNG families. Other generators get
+    /// copies of one stream that are skipped ahead by multiples of `block`, so each stream can
+    /// draw `block` numbers before reaching the next one, or, where skip-ahead is unsupported,
+    /// that are leapfrogged. A zero `block` is rejected with `BadArguments` for those generators.
+    pub fn new_independent(brng: VslBrng, seed: u32, count: usize, block: u64) -> Result<Vec<Self>, VslError> {
+        let family_size = BrngId::family_size(brng) as usize;
+        if family_size > 1 && count <= family_size {
+            return (0..count as u32).map(|i| Self::new(BrngId::new(brng, i)?, seed)).collect();
+        }
+
+        vsl_check(block > 0)?;
+        let base = Self::new(brng, seed)?;
+        match Self::skipped_ahead(&base, count, block) {
+            Err(VslError::SkipaheadUnsupported | VslError::SkipaheadXUnsupported) => Self::leapfrogged(&base, count),
+            result => result,
+        }
+    }
+
+    fn skipped_ahead(base: &Self, count: usize, block: u64) -> Result<Vec<Self>, VslError> {
+        (0..count as u128).map(|i| {
+            let mut stream = base.try_clone()?;
+            let nskip = i * block as u128;
+            match u64::try_from(nskip) {
+                Ok(nskip) => stream.skip_ahead(nskip)?,
+                Err(_) => stream.skip_ahead_ex(nskip)?,
+            }
+            Ok(stream)
+        }).collect()
+    }
+
+    fn leapfrogged(base: &Self, count: usize) -> Result<Vec<Self>, VslError> {
+        (0..count).map(|k| {
+            let mut stream = base.try_clone()?;
+            stream.leapfrog(k, count)?;
+            Ok(stream)
+        }).collect()
+    }
+}
+
+
+const SOBOL_MAX_DIMENSION: u32 = 40;
+const NIEDERREITER_MAX_DIMENSION: u32 = 318;
+
+fn vsl_path(path: &Path) -> Result<CString, VslError> {
+    path.to_str().and_then(|path| CString::new(path).ok()).ok_or(VslError::BadArguments)
+}
+
+impl VslStream {
+    pub fn save_to_path(&self, path: impl AsRef<Path>) -> Result<(), VslError> {
+        if self.is_abstract() {
+            return Err(VslError::InvalidAbstractStream);
+        }
+        let path = vsl_path(path.as_ref())?;
+        vsl_status(unsafe { sys::vslSaveStreamF(self.state, path.as_ptr()) })
+    }
+
+    pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self, VslError> {
+        let path = vsl_path(path.as_ref())?;
+        let mut state = std::ptr::null_mut();
+        vsl_status(unsafe { sys::vslLoadStreamF(&mut state, path.as_ptr()) })?;
+        Ok(Self { state })
+    }
+
+    pub fn stream_size(&self) -> Result<usize, VslError> {
+        match unsafe { sys::vslGetStreamSize(self.state) } {
+            size if size < 0 => Err(VslError::try_from(size).unwrap_or(VslError::BadStream)),
+            size => Ok(size as usize),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, VslError> {
+        if self.is_abstract() {
+            return Err(VslError::InvalidAbstractStream);
+        }
+        let mut bytes = vec![0u8; self.stream_size()?];
+        vsl_status(unsafe { sys::vslSaveStreamM(self.state, bytes.as_mut_ptr() as *mut c_char) })?;
+        Ok(bytes)
+    }
+
+    // The size of a stream depends on its parameters only for the quasi-random BRNGs, where it
+    // grows with the dimension.
+    fn max_stream_size(brng: StreamBrng) -> Result<usize, VslError> {
+        let stream = match brng {
+            StreamBrng::BuiltIn(id) if id.brng() == VslBrng::Sobol => Self::with_seed(&VslSeed::sobol(SOBOL_MAX_DIMENSION))?,
+            StreamBrng::BuiltIn(id) if id.brng() == VslBrng::Niederreiter => Self::with_seed(&VslSeed::niederreiter(NIEDERREITER_MAX_DIMENSION))?,
+            brng => Self::new(brng, 0)?,
+        };
+        stream.stream_size()
+    }
+
+    /// Restores a `brng` stream saved with `to_bytes`.
+    ///
+    /// MKL trusts the size in the header of `bytes`, so `bytes` may be no longer than the largest
+    /// `brng` stream and is zero-padded to that size before loading; a header that disagrees with
+    /// `bytes.len()` is then rejected. Sobol and Niederreiter streams are bounded by their largest
+    /// dimension with default direction numbers, 40 and 318.
+    pub fn from_bytes(brng: impl Into<StreamBrng>, bytes: &[u8]) -> Result<Self, VslError> {
+        let brng = brng.into();
+        let max_size = Self::max_stream_size(brng)?;
+        if bytes.is_empty() || bytes.len() > max_size {
+            return Err(VslError::BadMemoryFormat);
+        }
+        let mut padded = bytes.to_vec();
+        padded.resize(max_size, 0);
+
+        let mut state = std::ptr::null_mut();
+        vsl_status(unsafe { sys::vslLoadStreamM(&mut state, padded.as_ptr() as *const c_char) })?;
+        let stream = Self { state };
+        match stream.brng()? == brng && stream.stream_size()? == bytes.len() {
+            true => Ok(stream),
+            false => Err(VslError::BadMemoryFormat),
+        }
+    }
+}
+
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "VslStream")]
+struct SerializedStream {
+    brng: i32,
+    state: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for VslStream {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        SerializedStream {
+            brng: unsafe { sys::vslGetStreamStateBrng(self.state) },
+            state: self.to_bytes().map_err(|error| S::Error::custom(format!("{error:?}")))?,
+        }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VslStream {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let serialized = SerializedStream::deserialize(deserializer)?;
+        let brng = StreamBrng::try_from(serialized.brng).map_err(|error| D::Error::custom(format!("{error:?}")))?;
+        Self::from_bytes(brng, &serialized.state).map_err(|error| D::Error::custom(format!("{error:?}")))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{free_buffers, VslBrng};
+
+    fn mean(r: &[f64]) -> f64 {
+        r.iter().sum::<f64>() / r.len() as f64
+    }
+
+    #[test]
+    fn test_gaussian() {
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+        let mut r = vec![0.0; 10000];
+
+        for method in [VslRngMethodGaussian::BoxMuller, VslRngMethodGaussian::BoxMuller2, VslRngMethodGaussian::Icdf] {
+            stream.gaussian(method, &mut r, 5.0, 2.0).unwrap();
+
+            assert!((mean(&r) - 5.0).abs() < 0.1);
+        }
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_buffer_rng_gaussian() {
+        let mut buf: Buffer<f32> = Buffer::new_align_64(1000);
+
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+
+        buf.rng_gaussian(VslRngMethodGaussian::Icdf, &stream, 0.0, 1.0).unwrap();
+
+        free_buffers();
+
+        assert!(buf.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn test_supports() {
+        let stream = VslStream::new(VslBrng::MT19937, 7).unwrap();
+        let mut r = vec![0.0; 1000];
+
+        stream.uniform(VslRngMethodUniform::Accurate, &mut r, -1.0, 1.0).unwrap();
+        assert!(r.iter().all(|&x| (-1.0..=1.0).contains(&x)));
+
+        stream.exponential(VslRngMethodExponential::Icdf, &mut r, 2.0, 1.0).unwrap();
+        assert!(r.iter().all(|&x| x >= 2.0));
+
+        stream.weibull(VslRngMethodWeibull::IcdfAccurate, &mut r, 1.5, 1.0, 2.0).unwrap();
+        assert!(r.iter().all(|&x| x >= 1.0));
+
+        stream.rayleigh(VslRngMethodRayleigh::Icdf, &mut r, 0.0, 1.0).unwrap();
+        assert!(r.iter().all(|&x| x >= 0.0));
+
+        stream.lognormal(VslRngMethodLognormal::BoxMuller2, &mut r, 0.0, 1.0, 3.0, 1.0).unwrap();
+        assert!(r.iter().all(|&x| x >= 3.0));
+
+        stream.gamma(VslRngMethodGamma::GNorm, &mut r, 2.0, 0.0, 1.0).unwrap();
+        assert!(r.iter().all(|&x| x >= 0.0));
+        assert!((mean(&r) - 2.0).abs() < 0.2);
+
+        stream.beta(VslRngMethodBeta::Cja, &mut r, 2.0, 3.0, 0.0, 1.0).unwrap();
+        assert!(r.iter().all(|&x| (0.0..=1.0).contains(&x)));
+
+        stream.chi_square(VslRngMethodChiSquare::Chi2Gamma, &mut r, 4).unwrap();
+        assert!(r.iter().all(|&x| x >= 0.0));
+
+        stream.laplace(VslRngMethodLaplace::Icdf, &mut r, 0.0, 1.0).unwrap();
+        stream.cauchy(VslRngMethodCauchy::Icdf, &mut r, 0.0, 1.0).unwrap();
+        stream.gumbel(VslRngMethodGumbel::Icdf, &mut r, 0.0, 1.0).unwrap();
+        assert!(r.iter().all(|x| x.is_finite()));
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_poisson_binomial() {
+        let stream = VslStream::new(VslBrng::MT19937, 7).unwrap();
+        let mut r = vec![0; 10000];
+
+        for method in [VslRngMethodPoisson::Ptpe, VslRngMethodPoisson::PoisNorm] {
+            stream.poisson(method, &mut r, 20.0).unwrap();
+
+            let mean = r.iter().sum::<i32>() as f64 / r.len() as f64;
+            assert!((mean - 20.0).abs() < 0.5);
+        }
+
+        stream.binomial(VslRngMethodBinomial::Btpe, &mut r, 10, 0.3).unwrap();
+
+        assert!(r.iter().all(|&x| (0..=10).contains(&x)));
+        let mean = r.iter().sum::<i32>() as f64 / r.len() as f64;
+        assert!((mean - 3.0).abs() < 0.1);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_discrete_supports() {
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+        let mut r: Buffer<i32> = Buffer::new_align_64(1000);
+
+        r.rng_bernoulli(VslRngMethodBernoulli::Icdf, &stream, 0.5).unwrap();
+        assert!(r.iter().all(|&x| x == 0 || x == 1));
+
+        r.rng_geometric(VslRngMethodGeometric::Icdf, &stream, 0.5).unwrap();
+        assert!(r.iter().all(|&x| x >= 0));
+
+        r.rng_hypergeometric(VslRngMethodHypergeometric::H2pe, &stream, 50, 10, 20).unwrap();
+        assert!(r.iter().all(|&x| (0..=10).contains(&x)));
+
+        r.rng_neg_binomial(VslRngMethodNegBinomial::Nbar, &stream, 2.0, 0.5).unwrap();
+        assert!(r.iter().all(|&x| x >= 0));
+
+        let lambda: Vec<f64> = (1..=1000).map(|i| i as f64 / 100.0).collect();
+        r.rng_poisson_v(VslRngMethodPoissonV::PoisNorm, &stream, &lambda).unwrap();
+        assert!(r.iter().all(|&x| x >= 0));
+
+        let p = [0.2, 0.3, 0.5];
+        let mut counts = [0; 30];
+        stream.multinomial(VslRngMethodMultinomial::MultPoisson, &mut counts, 7, &p).unwrap();
+        assert!(counts.chunks(3).all(|c| c.iter().sum::<i32>() == 7));
+
+        let mut bits: Buffer<u64> = Buffer::new_align_64(16);
+        bits.rng_uniform_bits64(VslRngMethodUniformBits64::Std, &stream).unwrap();
+        assert!(bits.iter().any(|&x| x > u32::MAX as u64));
+
+        let mut bits = [0u32; 16];
+        stream.uniform_bits32(VslRngMethodUniformBits32::Std, &mut bits).unwrap();
+        stream.uniform_bits(VslRngMethodUniformBits::Std, &mut bits).unwrap();
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_invalid_discrete_parameters() {
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+        let mut r = [0; 6];
+
+        assert_eq!(stream.bernoulli(VslRngMethodBernoulli::Icdf, &mut r, 1.5), Err(VslError::BadArguments));
+        assert_eq!(stream.poisson(VslRngMethodPoisson::Ptpe, &mut r, 0.0), Err(VslError::BadArguments));
+        assert_eq!(stream.poisson_v(VslRngMethodPoissonV::PoisNorm, &mut r, &[1.0; 5]), Err(VslError::BadArguments));
+        assert_eq!(stream.binomial(VslRngMethodBinomial::Btpe, &mut r, -1, 0.5), Err(VslError::BadArguments));
+        assert_eq!(stream.hypergeometric(VslRngMethodHypergeometric::H2pe, &mut r, 10, 11, 5), Err(VslError::BadArguments));
+        assert_eq!(stream.multinomial(VslRngMethodMultinomial::MultPoisson, &mut r, 5, &[0.25; 4]), Err(VslError::BadArguments));
+
+        free_buffers();
+    }
+
+    fn covariance(r: &[f64], dimen: usize, i: usize, j: usize) -> f64 {
+        let rows = r.len() / dimen;
+        let mi = r.iter().skip(i).step_by(dimen).sum::<f64>() / rows as f64;
+        let mj = r.iter().skip(j).step_by(dimen).sum::<f64>() / rows as f64;
+        r.chunks(dimen).map(|row| (row[i] - mi) * (row[j] - mj)).sum::<f64>() / rows as f64
+    }
+
+    #[test]
+    fn test_gaussian_mv() {
+        let stream = VslStream::new(VslBrng::MT19937, 3).unwrap();
+        let mut r: Buffer<f64> = Buffer::new_align_64(2 * 20000);
+
+        // Σ = [[4, 2], [2, 2]] = Uᵀ U with U = [[2, 1], [0, 1]]
+        let factors = [
+            CholeskyFactor::full(2, vec![2.0, 1.0, 0.0, 1.0]).unwrap(),
+            CholeskyFactor::packed(2, vec![2.0, 1.0, 1.0]).unwrap(),
+        ];
+
+        for factor in factors {
+            let dist = GaussianMv::new(vec![1.0, -2.0], factor).unwrap();
+            r.rng_gaussian_mv(VslRngMethodGaussianMv::BoxMuller2, &stream, &dist).unwrap();
+
+            let first = r.iter().step_by(2).sum::<f64>() / 20000.0;
+            let second = r.iter().skip(1).step_by(2).sum::<f64>() / 20000.0;
+            assert!((first - 1.0).abs() < 0.1);
+            assert!((second + 2.0).abs() < 0.1);
+            assert!((covariance(&r, 2, 0, 0) - 4.0).abs() < 0.2);
+            assert!((covariance(&r, 2, 0, 1) - 2.0).abs() < 0.2);
+            assert!((covariance(&r, 2, 1, 1) - 2.0).abs() < 0.2);
+        }
+
+        let dist = GaussianMv::new(vec![0.0; 3], CholeskyFactor::diagonal(vec![1.0, 2.0, 3.0]).unwrap()).unwrap();
+        let mut r = vec![0.0; 3 * 20000];
+        stream.gaussian_mv(VslRngMethodGaussianMv::Icdf, &mut r, &dist).unwrap();
+
+        for (i, sigma) in [1.0, 2.0, 3.0].into_iter().enumerate() {
+            assert!((covariance(&r, 3, i, i) / (sigma * sigma) - 1.0).abs() < 0.05);
+        }
+        assert!(covariance(&r, 3, 0, 2).abs() < 0.1);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_gaussian_mv_shapes() {
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+
+        assert_eq!(CholeskyFactor::full(2, vec![1.0f64; 3]), Err(VslError::BadArguments));
+        assert_eq!(CholeskyFactor::packed(3, vec![1.0f64; 9]), Err(VslError::BadArguments));
+        assert_eq!(CholeskyFactor::packed(usize::MAX, vec![1.0f64; 3]), Err(VslError::BadArguments));
+        assert_eq!(CholeskyFactor::<f64>::diagonal(vec![]), Err(VslError::BadArguments));
+
+        let factor = CholeskyFactor::packed(3, vec![1.0f32; 6]).unwrap();
+        assert_eq!(factor.storage(), VslMatrixStorage::Packed);
+        assert_eq!(GaussianMv::new(vec![0.0; 2], factor.clone()), Err(VslError::BadArguments));
+
+        let dist = GaussianMv::new(vec![0.0; 3], factor).unwrap();
+        let mut r = [0.0f32; 10];
+        assert_eq!(stream.gaussian_mv(VslRngMethodGaussianMv::BoxMuller, &mut r, &dist), Err(VslError::BadArguments));
+        stream.gaussian_mv(VslRngMethodGaussianMv::BoxMuller, &mut r[..9], &dist).unwrap();
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_skip_ahead() {
+        let stream = VslStream::new(VslBrng::MRG32k3a, 5).unwrap();
+        let mut skipped = stream.clone();
+        let mut skipped_ex = stream.clone();
+
+        let mut r = [0.0f64; 16];
+        stream.uniform(VslRngMethodUniform::Fast, &mut r, 0.0, 1.0).unwrap();
+
+        let mut tail = [0.0f64; 8];
+        skipped.skip_ahead(8).unwrap();
+        skipped.uniform(VslRngMethodUniform::Fast, &mut tail, 0.0, 1.0).unwrap();
+        assert_eq!(tail, r[8..]);
+
+        skipped_ex.skip_ahead_ex(8).unwrap();
+        skipped_ex.uniform(VslRngMethodUniform::Fast, &mut tail, 0.0, 1.0).unwrap();
+        assert_eq!(tail, r[8..]);
+
+        let mut mt2203 = VslStream::new(VslBrng::MT2203, 5).unwrap();
+        assert_eq!(mt2203.skip_ahead(8), Err(VslError::SkipaheadUnsupported));
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_leapfrog() {
+        let stream = VslStream::new(VslBrng::MCG31, 5).unwrap();
+        let mut leapfrogged = stream.clone();
+        leapfrogged.leapfrog(1, 4).unwrap();
+
+        let mut r = [0.0f64; 16];
+        let mut every_fourth = [0.0f64; 4];
+        stream.uniform(VslRngMethodUniform::Fast, &mut r, 0.0, 1.0).unwrap();
+        leapfrogged.uniform(VslRngMethodUniform::Fast, &mut every_fourth, 0.0, 1.0).unwrap();
+        assert!(every_fourth.iter().zip(r.iter().skip(1).step_by(4)).all(|(a, b)| a == b));
+
+        assert_eq!(leapfrogged.leapfrog(4, 4), Err(VslError::BadArguments));
+        let mut mt19937 = VslStream::new(VslBrng::MT19937, 5).unwrap();
+        assert_eq!(mt19937.leapfrog(0, 2), Err(VslError::LeapfrogUnsupported));
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_new_independent() {
+        for brng in [VslBrng::MT2203, VslBrng::WichmannHill, VslBrng::Philox4x32x10, VslBrng::MCG31] {
+            let streams = VslStream::new_independent(brng, 1, 4, 1 << 20).unwrap();
+            assert_eq!(streams.len(), 4);
+
+            let draws: Vec<[f64; 4]> = streams.iter().map(|stream| {
+                let mut r = [0.0; 4];
+                stream.uniform(VslRngMethodUniform::Fast, &mut r, 0.0, 1.0).unwrap();
+                r
+            }).collect();
+            assert!(draws.windows(2).all(|w| w[0] != w[1]));
+        }
+
+        assert_eq!(VslStream::new_independent(VslBrng::MT2203, 1, 4, 0).unwrap().len(), 4);
+        assert_eq!(VslStream::new_independent(VslBrng::Philox4x32x10, 1, 4, 0).err(), Some(VslError::BadArguments));
+
+        free_buffers();
+    }
+
+    const PERSISTABLE_BRNGS: [VslBrng; 12] = [
+        VslBrng::MCG31, VslBrng::R250, VslBrng::MRG32k3a, VslBrng::MCG59, VslBrng::WichmannHill, VslBrng::MT19937,
+        VslBrng::MT2203, VslBrng::SFMT19937, VslBrng::Sobol, VslBrng::Niederreiter, VslBrng::Philox4x32x10, VslBrng::ARS5,
+    ];
+
+    fn assert_same_sequence(a: &VslStream, b: &VslStream) {
+        let mut ra = [0.0f64; 8];
+        let mut rb = [0.0f64; 8];
+        a.uniform(VslRngMethodUniform::Fast, &mut ra, 0.0, 1.0).unwrap();
+        b.uniform(VslRngMethodUniform::Fast, &mut rb, 0.0, 1.0).unwrap();
+        assert_eq!(ra, rb);
+    }
+
+    fn advanced_stream(brng: VslBrng) -> Option<VslStream> {
+        let stream = match VslStream::new(brng, 7) {
+            Err(VslError::ARS5NotSupported) => return None,
+            stream => stream.unwrap(),
+        };
+        let mut r = [0.0f64; 13];
+        stream.uniform(VslRngMethodUniform::Fast, &mut r, 0.0, 1.0).unwrap();
+        Some(stream)
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        for brng in PERSISTABLE_BRNGS {
+            let Some(stream) = advanced_stream(brng) else { continue };
+
+            let bytes = stream.to_bytes().unwrap();
+            assert_eq!(bytes.len(), stream.stream_size().unwrap());
+
+            let restored = VslStream::from_bytes(brng, &bytes).unwrap();
+            assert_same_sequence(&stream, &restored);
+
+            assert!(VslStream::from_bytes(brng, &bytes[..bytes.len() - 1]).is_err());
+            assert_eq!(VslStream::from_bytes(brng, &[]).err(), Some(VslError::BadMemoryFormat));
+        }
+
+        let dimensions = vec![
+            JoeKuoDimension { degree: 1, coefficients: 0, initial: vec![1] },
+            JoeKuoDimension { degree: 2, coefficients: 1, initial: vec![1, 3] },
+        ];
+        let sobol = QuasiRandomStream::sobol(3).joe_kuo(dimensions).skip(5).build().unwrap();
+        let bytes = sobol.stream().to_bytes().unwrap();
+        let restored = VslStream::from_bytes(VslBrng::Sobol, &bytes).unwrap();
+        assert_same_sequence(sobol.stream(), &restored);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_path_round_trip() {
+        for brng in PERSISTABLE_BRNGS {
+            let Some(stream) = advanced_stream(brng) else { continue };
+
+            let path = std::env::temp_dir().join(format!("intel-mkl-stream-{}-{:?}.bin", std::process::id(), brng));
+            stream.save_to_path(&path).unwrap();
+            let restored = VslStream::load_from_path(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_same_sequence(&stream, &restored);
+        }
+
+        let missing = std::env::temp_dir().join("intel-mkl-missing-dir").join("stream.bin");
+        assert_eq!(VslStream::load_from_path(missing).err(), Some(VslError::FileOpen));
+
+        free_buffers();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let stream = advanced_stream(VslBrng::MT19937).unwrap();
+
+        let json = serde_json::to_string(&stream).unwrap();
+        let restored: VslStream = serde_json::from_str(&json).unwrap();
+        assert_same_sequence(&stream, &restored);
+
+        let mut value = serde_json::to_value(&stream).unwrap();
+        value["brng"] = serde_json::json!(i32::from(VslBrng::Philox4x32x10));
+        assert!(serde_json::from_value::<VslStream>(value).is_err());
+
+        let mut value = serde_json::to_value(&stream).unwrap();
+        value["state"].as_array_mut().unwrap().truncate(16);
+        assert!(serde_json::from_value::<VslStream>(value).is_err());
+        assert!(serde_json::from_str::<VslStream>(r#"{"brng":0,"state":[]}"#).is_err());
+
+        let brng: VslBrng = serde_json::from_str(&serde_json::to_string(&VslBrng::MT2203).unwrap()).unwrap();
+        assert_eq!(brng, VslBrng::MT2203);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let stream = VslStream::new(VslBrng::Philox4x32x10, 21).unwrap();
+        let mut r = [0.0f32; 8];
+
+        assert_eq!(stream.uniform(VslRngMethodUniform::Fast, &mut r, 1.0, 1.0), Err(VslError::BadArguments));
+        assert_eq!(stream.gaussian(VslRngMethodGaussian::Icdf, &mut r, 0.0, 0.0), Err(VslError::BadArguments));
+        assert_eq!(stream.gaussian(VslRngMethodGaussian::Icdf, &mut r, 0.0, f32::NAN), Err(VslError::BadArguments));
+        assert_eq!(stream.gamma(VslRngMethodGamma::GNorm, &mut r, -1.0, 0.0, 1.0), Err(VslError::BadArguments));
+        assert_eq!(stream.beta(VslRngMethodBeta::Cja, &mut r, 1.0, 0.0, 0.0, 1.0), Err(VslError::BadArguments));
+        assert_eq!(stream.chi_square(VslRngMethodChiSquare::Chi2Gamma, &mut r, 0), Err(VslError::BadArguments));
+
+        free_buffers();
+    }
+}
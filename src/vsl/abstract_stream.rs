@@ -0,0 +1,219 @@
+use std::{any::Any, collections::HashMap, ffi::c_int, mem::ManuallyDrop, ops::Deref, panic::{self, AssertUnwindSafe}, slice, sync::{Mutex, MutexGuard, OnceLock, PoisonError}};
+
+use crate::{sys, VslError, VslStream};
+use super::{vsl_check, vsl_len, vsl_status};
+
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u32 {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// Element types of the abstract BRNGs: `u32` (`IntegerAbstract`), `f32` (`SingleAbstract`) and
+/// `f64` (`DoubleAbstract`).
+#[allow(clippy::missing_safety_doc)]
+pub trait AbstractElement: Copy + Default + 'static + private::Sealed {
+    #[doc(hidden)]
+    const DEFAULT_BOUNDS: (Self, Self);
+
+    #[doc(hidden)]
+    unsafe fn vsl_new_abstract_stream(stream: *mut sys::VSLStreamStatePtr, n: c_int, buf: *const Self, a: Self, b: Self) -> c_int;
+}
+
+impl AbstractElement for u32 {
+    const DEFAULT_BOUNDS: (Self, Self) = (0, u32::MAX);
+
+    unsafe fn vsl_new_abstract_stream(stream: *mut sys::VSLStreamStatePtr, n: c_int, buf: *const Self, _a: Self, _b: Self) -> c_int {
+        sys::vsliNewAbstractStream(stream, n, buf, Some(refill_trampoline::<u32>))
+    }
+}
+
+impl AbstractElement for f32 {
+    const DEFAULT_BOUNDS: (Self, Self) = (0.0, 1.0);
+
+    unsafe fn vsl_new_abstract_stream(stream: *mut sys::VSLStreamStatePtr, n: c_int, buf: *const Self, a: Self, b: Self) -> c_int {
+        sys::vslsNewAbstractStream(stream, n, buf, a, b, Some(refill_trampoline::<f32>))
+    }
+}
+
+impl AbstractElement for f64 {
+    const DEFAULT_BOUNDS: (Self, Self) = (0.0, 1.0);
+
+    unsafe fn vsl_new_abstract_stream(stream: *mut sys::VSLStreamStatePtr, n: c_int, buf: *const Self, a: Self, b: Self) -> c_int {
+        sys::vsldNewAbstractStream(stream, n, buf, a, b, Some(refill_trampoline::<f64>))
+    }
+}
+
+
+type Refill<T> = Box<dyn FnMut(&mut [T]) -> usize + Send>;
+
+// MKL passes no user data to the update callback, so refill closures are looked up by the address
+// of the buffer they fill.
+static REFILLS: OnceLock<Mutex<HashMap<usize, Box<dyn Any + Send>>>> = OnceLock::new();
+
+fn refills() -> MutexGuard<'static, HashMap<usize, Box<dyn Any + Send>>> {
+    REFILLS.get_or_init(Default::default).lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn fill_cyclic<T>(refill: &mut Refill<T>, buf: &mut [T], idx: usize, count: usize) -> usize {
+    let first = count.min(buf.len() - idx);
+    let written = refill(&mut buf[idx..idx + first]).min(first);
+    match written < first || first == count {
+        true => written,
+        false => written + refill(&mut buf[..count - first]).min(count - first),
+    }
+}
+
+unsafe extern "C" fn refill_trampoline<T: AbstractElement>(
+    _stream: sys::VSLStreamStatePtr,
+    n: *mut c_int,
+    buf: *mut T,
+    _nmin: *mut c_int,
+    nmax: *mut c_int,
+    idx: *mut c_int,
+) -> c_int {
+    // The closure is taken out of the map while it runs so that it may use other abstract streams.
+    let Some(mut refill) = refills().remove(&(buf as usize)) else { return 0 };
+    let buffer = slice::from_raw_parts_mut(buf, *n as usize);
+    let (idx, nmax) = (*idx as usize, *nmax as usize);
+
+    let written = panic::catch_unwind(AssertUnwindSafe(|| {
+        fill_cyclic(refill.downcast_mut::<Refill<T>>().unwrap(), buffer, idx, nmax)
+    })).unwrap_or(0);
+
+    refills().insert(buf as usize, refill);
+    written as c_int
+}
+
+
+/// A stream of one of the abstract BRNGs that reads its numbers from a buffer refilled by a Rust
+/// closure.
+///
+/// The closure fills the slice it is given and returns how many elements it wrote; returning
+/// fewer than requested ends the sequence, after which generation fails with
+/// `VslError::NoNumbers`. Floating-point numbers are expected to lie in `[0, 1)` unless other
+/// bounds are passed to `with_bounds`.
+///
+/// The underlying `VslStream` refuses `try_clone`, `copy_state_from`, `to_bytes` and
+/// `save_to_path`, since a copy would keep reading from this stream's buffer after it is dropped;
+/// `clone()` panics for the same reason.
+pub struct AbstractStream<T: AbstractElement> {
+    stream: ManuallyDrop<VslStream>,
+    // Owned `Box<[T]>`, kept as a raw pointer because MKL and the refill closure write to it.
+    buffer: *mut [T],
+}
+
+unsafe impl<T: AbstractElement> Send for AbstractStream<T> {}
+
+impl<T: AbstractElement> AbstractStream<T> {
+    pub fn new(len: usize, refill: impl FnMut(&mut [T]) -> usize + Send + 'static) -> Result<Self, VslError> {
+        let (a, b) = T::DEFAULT_BOUNDS;
+        Self::with_bounds(len, a, b, refill)
+    }
+
+    pub fn with_bounds(len: usize, a: T, b: T, refill: impl FnMut(&mut [T]) -> usize + Send + 'static) -> Result<Self, VslError> {
+        vsl_check(len > 0)?;
+        let n = vsl_len(len)?;
+
+        let mut refill: Refill<T> = Box::new(refill);
+        let mut buffer = vec![T::default(); len].into_boxed_slice();
+        if fill_cyclic(&mut refill, &mut buffer, 0, len) < len {
+            return Err(VslError::NoNumbers);
+        }
+
+        let buffer = Box::into_raw(buffer);
+        let data = buffer as *mut T;
+        let mut state = std::ptr::null_mut();
+        if let Err(error) = vsl_status(unsafe { T::vsl_new_abstract_stream(&mut state, n, data, a, b) }) {
+            drop(unsafe { Box::from_raw(buffer) });
+            return Err(error);
+        }
+        refills().insert(data as usize, Box::new(refill));
+
+        Ok(Self { stream: ManuallyDrop::new(VslStream { state }), buffer })
+    }
+
+    pub fn stream(&self) -> &VslStream {
+        &self.stream
+    }
+}
+
+impl<T: AbstractElement> Deref for AbstractStream<T> {
+    type Target = VslStream;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+impl<T: AbstractElement> Drop for AbstractStream<T> {
+    // The stream is deleted before the buffer it reads from is freed.
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.stream) };
+        refills().remove(&(self.buffer as *mut T as usize));
+        drop(unsafe { Box::from_raw(self.buffer) });
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{free_buffers, StreamBrng, VslBrng, VslRngMethodGaussian, VslRngMethodUniform};
+
+    fn recorded(values: Vec<f64>) -> impl FnMut(&mut [f64]) -> usize + Send + 'static {
+        let mut values = values.into_iter();
+        move |buf: &mut [f64]| buf.iter_mut().zip(&mut values).map(|(x, v)| *x = v).count()
+    }
+
+    #[test]
+    fn test_recorded_sequence() {
+        let values: Vec<f64> = (0..12).map(|i| (i as f64 + 0.5) / 12.0).collect();
+        let stream = AbstractStream::new(5, recorded(values.clone())).unwrap();
+        assert_eq!(stream.brng(), Ok(StreamBrng::from(VslBrng::DoubleAbstract)));
+
+        let mut r = [0.0f64; 12];
+        stream.uniform(VslRngMethodUniform::Fast, &mut r, 0.0, 1.0).unwrap();
+        assert!(r.iter().zip(&values).all(|(a, b)| (a - b).abs() < 1e-12));
+
+        assert_eq!(stream.uniform(VslRngMethodUniform::Fast, &mut r, 0.0, 1.0), Err(VslError::NoNumbers));
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_distribution_transform() {
+        let mut state = 0x2545f491u32;
+        let stream = AbstractStream::<f32>::new(256, move |buf| {
+            for x in buf.iter_mut() {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                *x = (state >> 8) as f32 / (1 << 24) as f32;
+            }
+            buf.len()
+        }).unwrap();
+
+        let mut r = vec![0.0f32; 10000];
+        stream.gaussian(VslRngMethodGaussian::Icdf, &mut r, 3.0, 1.0).unwrap();
+        let mean = r.iter().sum::<f32>() / r.len() as f32;
+        assert!((mean - 3.0).abs() < 0.05);
+
+        assert_eq!(AbstractStream::<u32>::new(4, |_| 0).err(), Some(VslError::NoNumbers));
+
+        assert_eq!(stream.try_clone().err(), Some(VslError::InvalidAbstractStream));
+        let mut other = VslStream::new(VslBrng::MT19937, 1).unwrap();
+        assert_eq!(other.copy_state_from(&stream), Err(VslError::InvalidAbstractStream));
+        assert_eq!(stream.to_bytes().err(), Some(VslError::InvalidAbstractStream));
+        let path = std::env::temp_dir().join(format!("intel-mkl-abstract-{}.bin", std::process::id()));
+        assert_eq!(stream.save_to_path(&path), Err(VslError::InvalidAbstractStream));
+        #[cfg(feature = "serde")]
+        assert!(serde_json::to_string(&*stream).is_err());
+        assert_eq!(AbstractStream::<u32>::new(0, |buf| buf.len()).err(), Some(VslError::BadArguments));
+
+        free_buffers();
+    }
+}
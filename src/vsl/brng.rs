@@ -0,0 +1,116 @@
+use std::mem::MaybeUninit;
+
+use crate::{sys, BrngId, RegisteredBrng, StreamBrng, VslBrng, VslError, VslStream};
+use super::vsl_status;
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BrngProperties {
+    pub stream_state_size: usize,
+    pub n_seeds: usize,
+    pub includes_zero: bool,
+    pub word_size: usize,
+    pub n_bits: usize,
+}
+
+impl From<sys::VSLBRngProperties> for BrngProperties {
+    fn from(properties: sys::VSLBRngProperties) -> Self {
+        Self {
+            stream_state_size: properties.StreamStateSize.try_into().unwrap(),
+            n_seeds: properties.NSeeds.try_into().unwrap(),
+            includes_zero: properties.IncludesZero != 0,
+            word_size: properties.WordSize.try_into().unwrap(),
+            n_bits: properties.NBits.try_into().unwrap(),
+        }
+    }
+}
+
+fn brng_properties(brng: i32) -> Result<BrngProperties, VslError> {
+    let mut properties = MaybeUninit::<sys::VSLBRngProperties>::uninit();
+    vsl_status(unsafe { sys::vslGetBrngProperties(brng, properties.as_mut_ptr()) })?;
+    Ok(unsafe { properties.assume_init() }.into())
+}
+
+impl BrngId {
+    pub fn properties(&self) -> Result<BrngProperties, VslError> {
+        brng_properties((*self).into())
+    }
+}
+
+impl VslBrng {
+    pub fn properties(&self) -> Result<BrngProperties, VslError> {
+        BrngId::from(*self).properties()
+    }
+}
+
+impl RegisteredBrng {
+    pub fn properties(&self) -> Result<BrngProperties, VslError> {
+        brng_properties((*self).into())
+    }
+}
+
+impl StreamBrng {
+    pub fn properties(&self) -> Result<BrngProperties, VslError> {
+        brng_properties((*self).into())
+    }
+}
+
+// Ids outside the built-in families are accepted if MKL has them in its BRNG table.
+impl TryFrom<i32> for StreamBrng {
+    type Error = VslError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match BrngId::try_from(value) {
+            Ok(id) => Ok(id.into()),
+            Err(error) => match brng_properties(value) {
+                Ok(_) => Ok(RegisteredBrng::new(value).into()),
+                Err(_) => Err(error),
+            },
+        }
+    }
+}
+
+pub fn registered_brng_count() -> usize {
+    unsafe { sys::vslGetNumRegBrngs() }.try_into().unwrap()
+}
+
+
+impl VslStream {
+    pub fn brng(&self) -> Result<StreamBrng, VslError> {
+        match unsafe { sys::vslGetStreamStateBrng(self.state) } {
+            brng if brng < 0 => Err(VslError::try_from(brng).unwrap_or(VslError::BadStream)),
+            brng => StreamBrng::try_from(brng),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::free_buffers;
+
+    #[test]
+    fn test_brng_properties() {
+        let properties = VslBrng::MT19937.properties().unwrap();
+        assert_eq!(properties.word_size, 4);
+        assert_eq!(properties.n_bits, 32);
+        assert!(properties.stream_state_size > 0);
+
+        let family = BrngId::new(VslBrng::MT2203, 100).unwrap().properties().unwrap();
+        assert_eq!(family, VslBrng::MT2203.properties().unwrap());
+
+        assert!(registered_brng_count() >= 14);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_stream_brng() {
+        let id = BrngId::new(VslBrng::MT2203, 5).unwrap();
+        assert_eq!(VslStream::new(id, 1).unwrap().brng(), Ok(id.into()));
+        assert_eq!(VslStream::new(VslBrng::Philox4x32x10, 1).unwrap().brng(), Ok(VslBrng::Philox4x32x10.into()));
+
+        free_buffers();
+    }
+}
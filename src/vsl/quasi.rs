@@ -0,0 +1,200 @@
+use crate::{sys, VslBrng, VslError, VslRngMethodUniform, VslStream};
+use super::{vsl_check, VslFloat, VslSeed};
+
+
+/// Primitive polynomial and initial direction numbers of one Sobol dimension, as listed in the
+/// Joe-Kuo tables: `degree` is `s`, `coefficients` is `a` and `initial` holds `m_1, ..., m_s`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JoeKuoDimension {
+    pub degree: u32,
+    pub coefficients: u32,
+    pub initial: Vec<u32>,
+}
+
+impl JoeKuoDimension {
+    fn polynomial(&self) -> u32 {
+        (1 << self.degree) | (self.coefficients << 1) | 1
+    }
+}
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum DirectionNumbers {
+    Default,
+    Full(Vec<u32>),
+    JoeKuo(Vec<JoeKuoDimension>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct QuasiRandomStreamBuilder {
+    brng: VslBrng,
+    dimension: u32,
+    direction_numbers: DirectionNumbers,
+    skip: u64,
+}
+
+impl QuasiRandomStreamBuilder {
+    /// Uses a full table of direction numbers, 32 per dimension in row-major order.
+    pub fn direction_numbers(mut self, direction_numbers: Vec<u32>) -> Self {
+        self.direction_numbers = DirectionNumbers::Full(direction_numbers);
+        self
+    }
+
+    /// Uses Joe-Kuo primitive polynomials and initial direction numbers for dimensions `2..=dimension`;
+    /// the first dimension keeps MKL's initialization. Only supported for Sobol.
+    pub fn joe_kuo(mut self, dimensions: Vec<JoeKuoDimension>) -> Self {
+        self.direction_numbers = DirectionNumbers::JoeKuo(dimensions);
+        self
+    }
+
+    /// Skips the first `points` points of the sequence.
+    pub fn skip(mut self, points: u64) -> Self {
+        self.skip = points;
+        self
+    }
+
+    pub fn seed(&self) -> Result<VslSeed, VslError> {
+        vsl_check(self.dimension > 0)?;
+        match (&self.direction_numbers, self.brng) {
+            (DirectionNumbers::Default, VslBrng::Sobol) => Ok(VslSeed::sobol(self.dimension)),
+            (DirectionNumbers::Default, _) => Ok(VslSeed::niederreiter(self.dimension)),
+            (DirectionNumbers::Full(numbers), VslBrng::Sobol) => VslSeed::sobol_with_direction_numbers(self.dimension, numbers),
+            (DirectionNumbers::Full(numbers), _) => VslSeed::niederreiter_with_direction_numbers(self.dimension, numbers),
+            (DirectionNumbers::JoeKuo(dimensions), VslBrng::Sobol) => joe_kuo_seed(self.dimension, dimensions),
+            (DirectionNumbers::JoeKuo(_), _) => Err(VslError::BadArguments),
+        }
+    }
+
+    pub fn build(self) -> Result<QuasiRandomStream, VslError> {
+        let mut stream = VslStream::with_seed(&self.seed()?)?;
+        if self.skip > 0 {
+            stream.skip_ahead_ex(self.skip as u128 * self.dimension as u128)?;
+        }
+        Ok(QuasiRandomStream { stream, dimension: self.dimension as usize })
+    }
+}
+
+fn joe_kuo_seed(dimension: u32, dimensions: &[JoeKuoDimension]) -> Result<VslSeed, VslError> {
+    vsl_check(dimensions.len() + 1 == dimension as usize)?;
+    vsl_check(dimensions.iter().all(|d| d.degree > 0 && d.degree < 32 && d.initial.len() == d.degree as usize))?;
+
+    let first = JoeKuoDimension { degree: 0, coefficients: 0, initial: vec![] };
+    let dimensions: Vec<&JoeKuoDimension> = std::iter::once(&first).chain(dimensions).collect();
+    let max_degree = dimensions.iter().map(|d| d.degree).max().unwrap().max(1);
+
+    let mut words = vec![dimension, sys::VSL_USER_QRNG_INITIAL_VALUES, sys::VSL_USER_PRIMITIVE_POLYMS | sys::VSL_USER_INIT_DIRECTION_NUMBERS];
+    words.extend(dimensions.iter().map(|d| d.polynomial()));
+    words.push(max_degree);
+    for d in &dimensions {
+        words.extend(d.initial.iter().copied().chain(std::iter::repeat(1)).take(max_degree as usize));
+    }
+    Ok(VslSeed::new(VslBrng::Sobol, &words))
+}
+
+
+/// A Sobol or Niederreiter stream that produces points of a fixed dimension.
+///
+/// Once the sequence period is exhausted MKL reports `VslError::QrngPeriodElapsed`, which is
+/// returned unchanged from `fill_points` and `points`.
+pub struct QuasiRandomStream {
+    stream: VslStream,
+    dimension: usize,
+}
+
+impl QuasiRandomStream {
+    pub fn sobol(dimension: u32) -> QuasiRandomStreamBuilder {
+        QuasiRandomStreamBuilder { brng: VslBrng::Sobol, dimension, direction_numbers: DirectionNumbers::Default, skip: 0 }
+    }
+
+    pub fn niederreiter(dimension: u32) -> QuasiRandomStreamBuilder {
+        QuasiRandomStreamBuilder { brng: VslBrng::Niederreiter, dimension, direction_numbers: DirectionNumbers::Default, skip: 0 }
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    pub fn stream(&self) -> &VslStream {
+        &self.stream
+    }
+
+    /// Fills `r` with `r.len() / self.dimension()` points in `[0, 1)`, one per row.
+    pub fn fill_points<T: VslFloat>(&self, r: &mut [T]) -> Result<(), VslError> {
+        vsl_check(r.len().is_multiple_of(self.dimension))?;
+        self.stream.uniform(VslRngMethodUniform::Fast, r, T::default(), T::ONE)
+    }
+
+    /// Returns the next `n` points as a row-major `n x self.dimension()` matrix.
+    pub fn points<T: VslFloat>(&self, n: usize) -> Result<Vec<T>, VslError> {
+        let len = n.checked_mul(self.dimension).ok_or(VslError::BadArguments)?;
+        let mut r = vec![T::default(); len];
+        self.fill_points(&mut r)?;
+        Ok(r)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::free_buffers;
+
+    fn column_mean(points: &[f64], dimension: usize, column: usize) -> f64 {
+        points.iter().skip(column).step_by(dimension).sum::<f64>() / (points.len() / dimension) as f64
+    }
+
+    #[test]
+    fn test_points() {
+        for builder in [QuasiRandomStream::sobol(3), QuasiRandomStream::niederreiter(3)] {
+            let stream = builder.build().unwrap();
+            assert_eq!(stream.dimension(), 3);
+
+            let points: Vec<f64> = stream.points(1024).unwrap();
+            assert_eq!(points.len(), 3 * 1024);
+            assert!(points.iter().all(|x| (0.0..1.0).contains(x)));
+            for column in 0..3 {
+                assert!((column_mean(&points, 3, column) - 0.5).abs() < 0.01);
+            }
+
+            let mut r = [0.0f32; 4];
+            assert_eq!(stream.fill_points(&mut r), Err(VslError::BadArguments));
+        }
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_skip() {
+        let all: Vec<f64> = QuasiRandomStream::sobol(2).build().unwrap().points(10).unwrap();
+        let skipped: Vec<f64> = QuasiRandomStream::sobol(2).skip(4).build().unwrap().points(6).unwrap();
+        assert_eq!(skipped, all[8..]);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_joe_kuo() {
+        let dimensions = vec![
+            JoeKuoDimension { degree: 1, coefficients: 0, initial: vec![1] },
+            JoeKuoDimension { degree: 2, coefficients: 1, initial: vec![1, 3] },
+        ];
+
+        let seed = QuasiRandomStream::sobol(3).joe_kuo(dimensions.clone()).seed().unwrap();
+        assert_eq!(seed.words(), [
+            3, sys::VSL_USER_QRNG_INITIAL_VALUES, sys::VSL_USER_PRIMITIVE_POLYMS | sys::VSL_USER_INIT_DIRECTION_NUMBERS,
+            0b1, 0b11, 0b111, 2, 1, 1, 1, 1, 1, 3,
+        ]);
+
+        let stream = QuasiRandomStream::sobol(3).joe_kuo(dimensions.clone()).build().unwrap();
+        let points: Vec<f64> = stream.points(1024).unwrap();
+        for column in 0..3 {
+            assert!((column_mean(&points, 3, column) - 0.5).abs() < 0.01);
+        }
+
+        assert_eq!(QuasiRandomStream::sobol(4).joe_kuo(dimensions.clone()).build().err(), Some(VslError::BadArguments));
+        assert_eq!(QuasiRandomStream::niederreiter(3).joe_kuo(dimensions).build().err(), Some(VslError::BadArguments));
+        assert_eq!(QuasiRandomStream::sobol(0).build().err(), Some(VslError::BadArguments));
+
+        free_buffers();
+    }
+}
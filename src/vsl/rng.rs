@@ -0,0 +1,146 @@
+use std::num::NonZeroU32;
+
+use rand_core::{impls, Error, RngCore, SeedableRng};
+
+use crate::{Buffer, VslError, VslRngMethodUniformBits32, VslStream};
+use super::VslSeed;
+
+
+const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// A `rand_core::RngCore` over a `VslStream`.
+///
+/// Words are generated with `viRngUniformBits32` in batches into an aligned buffer, so the cost of
+/// each FFI call is spread over the whole batch. `next_u64` joins two of those words rather than
+/// calling `viRngUniformBits64`, so the output does not depend on how 32- and 64-bit requests
+/// are interleaved and no second buffer is needed.
+pub struct VslRng {
+    stream: VslStream,
+    buffer: Buffer<u32>,
+    pos: usize,
+}
+
+impl VslRng {
+    pub fn new(stream: VslStream) -> Result<Self, VslError> {
+        Self::with_batch_size(stream, DEFAULT_BATCH_SIZE)
+    }
+
+    /// Fills the first batch right away, so BRNGs without `viRngUniformBits32` support are
+    /// rejected here with `VslError::BrngNotSupported` instead of panicking later.
+    pub fn with_batch_size(stream: VslStream, batch_size: usize) -> Result<Self, VslError> {
+        let mut buffer = Buffer::new_align_64(batch_size.max(1));
+        stream.uniform_bits32(VslRngMethodUniformBits32::Std, buffer.as_mut_slice())?;
+        Ok(Self { stream, buffer, pos: 0 })
+    }
+
+    pub fn stream(&self) -> &VslStream {
+        &self.stream
+    }
+
+    /// Returns the stream; words that were generated but not handed out are discarded.
+    pub fn into_inner(self) -> VslStream {
+        self.stream
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, VslError> {
+        if self.pos == self.buffer.len() {
+            self.stream.uniform_bits32(VslRngMethodUniformBits32::Std, self.buffer.as_mut_slice())?;
+            self.pos = 0;
+        }
+        self.pos += 1;
+        Ok(self.buffer[self.pos - 1])
+    }
+
+    fn try_fill(&mut self, dest: &mut [u8]) -> Result<(), VslError> {
+        for chunk in dest.chunks_mut(4) {
+            let word = self.try_next_u32()?.to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+fn rand_error(error: VslError) -> Error {
+    let code = Error::CUSTOM_START + i32::from(error).unsigned_abs();
+    NonZeroU32::new(code).unwrap().into()
+}
+
+impl RngCore for VslRng {
+    fn next_u32(&mut self) -> u32 {
+        self.try_next_u32().unwrap()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill(dest).unwrap()
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.try_fill(dest).map_err(rand_error)
+    }
+}
+
+/// Seeds a Philox4x32x10 stream; the 24 seed bytes are the little-endian 64-bit key followed by the
+/// 128-bit counter.
+impl SeedableRng for VslRng {
+    type Seed = [u8; 24];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let key = u64::from_le_bytes(seed[..8].try_into().unwrap());
+        let counter = u128::from_le_bytes(seed[8..].try_into().unwrap());
+        Self::new(VslStream::with_seed(&VslSeed::philox(key, counter)).unwrap()).unwrap()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{distributions::Uniform, Rng};
+
+    use super::*;
+    use crate::{free_buffers, VslBrng};
+
+    #[test]
+    fn test_words_match_stream() {
+        let mut rng = VslRng::with_batch_size(VslStream::new(VslBrng::MT19937, 5).unwrap(), 3).unwrap();
+        let words: Vec<u32> = (0..10).map(|_| rng.next_u32()).collect();
+
+        let mut expected = [0u32; 10];
+        VslStream::new(VslBrng::MT19937, 5).unwrap().uniform_bits32(VslRngMethodUniformBits32::Std, &mut expected).unwrap();
+        assert_eq!(words, expected);
+
+        let mut bytes = [0u8; 6];
+        let mut rng = VslRng::new(VslStream::new(VslBrng::MT19937, 5).unwrap()).unwrap();
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes[..4], expected[0].to_le_bytes());
+        assert_eq!(bytes[4..], expected[1].to_le_bytes()[..2]);
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_rand_distributions() {
+        let mut rng = VslRng::seed_from_u64(42);
+        let dice = Uniform::new_inclusive(1, 6);
+        let rolls: Vec<i32> = (&mut rng).sample_iter(dice).take(6000).collect();
+        assert!(rolls.iter().all(|x| (1..=6).contains(x)));
+        assert!((1..=6).all(|face| rolls.iter().filter(|&&x| x == face).count() > 800));
+
+        let x: f64 = rng.gen();
+        assert!((0.0..1.0).contains(&x));
+
+        let mut seed = [0u8; 24];
+        seed[0] = 21;
+        let mut a = VslRng::from_seed(seed);
+        let mut b = VslRng::new(VslStream::new(VslBrng::Philox4x32x10, 21).unwrap()).unwrap();
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let stream = VslStream::new(VslBrng::MCG31, 1).unwrap();
+        assert_eq!(VslRng::new(stream).err(), Some(VslError::BrngNotSupported));
+
+        free_buffers();
+    }
+}
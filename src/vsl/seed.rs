@@ -0,0 +1,139 @@
+use std::ffi::c_int;
+
+use crate::{sys, StreamBrng, VslBrng, VslError, VslStream};
+use super::vsl_status;
+
+
+/// Initialization parameters for `vslNewStreamEx`, tagged with the BRNG they are meant for.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VslSeed {
+    brng: StreamBrng,
+    words: Vec<u32>,
+}
+
+impl VslSeed {
+    /// Raw seed words, interpreted as documented for `brng` in the MKL VS notes.
+    pub fn new(brng: impl Into<StreamBrng>, words: &[u32]) -> Self {
+        Self { brng: brng.into(), words: words.to_vec() }
+    }
+
+    /// Key `k` and counter `c` of Philox4x32x10.
+    pub fn philox(key: u64, counter: u128) -> Self {
+        let mut words = split_words(key.into(), 2);
+        words.extend(split_words(counter, 4));
+        Self { brng: StreamBrng::from(VslBrng::Philox4x32x10), words }
+    }
+
+    /// Key `k` and counter `c` of ARS-5.
+    pub fn ars5(key: u128, counter: u128) -> Self {
+        let mut words = split_words(key, 4);
+        words.extend(split_words(counter, 4));
+        Self { brng: StreamBrng::from(VslBrng::ARS5), words }
+    }
+
+    /// Sobol sequence of the given dimension with MKL's default direction numbers.
+    pub fn sobol(dimension: u32) -> Self {
+        Self { brng: StreamBrng::from(VslBrng::Sobol), words: vec![dimension] }
+    }
+
+    /// Sobol sequence with user-defined direction numbers, 32 per dimension in row-major order.
+    pub fn sobol_with_direction_numbers(dimension: u32, direction_numbers: &[u32]) -> Result<Self, VslError> {
+        Ok(Self { brng: StreamBrng::from(VslBrng::Sobol), words: direction_number_words(dimension, direction_numbers)? })
+    }
+
+    /// Niederreiter sequence of the given dimension with MKL's default irreducible polynomials.
+    pub fn niederreiter(dimension: u32) -> Self {
+        Self { brng: StreamBrng::from(VslBrng::Niederreiter), words: vec![dimension] }
+    }
+
+    /// Niederreiter sequence with user-defined direction numbers, 32 per dimension in row-major
+    /// order.
+    pub fn niederreiter_with_direction_numbers(dimension: u32, direction_numbers: &[u32]) -> Result<Self, VslError> {
+        Ok(Self { brng: StreamBrng::from(VslBrng::Niederreiter), words: direction_number_words(dimension, direction_numbers)? })
+    }
+
+    pub fn brng(&self) -> StreamBrng {
+        self.brng
+    }
+
+    pub fn words(&self) -> &[u32] {
+        &self.words
+    }
+}
+
+fn split_words(value: u128, count: usize) -> Vec<u32> {
+    (0..count).map(|i| (value >> (32 * i)) as u32).collect()
+}
+
+fn direction_number_words(dimension: u32, direction_numbers: &[u32]) -> Result<Vec<u32>, VslError> {
+    match (dimension as usize).checked_mul(32) == Some(direction_numbers.len()) {
+        true => Ok([dimension, sys::VSL_USER_QRNG_INITIAL_VALUES, sys::VSL_USER_DIRECTION_NUMBERS].into_iter().chain(direction_numbers.iter().copied()).collect()),
+        false => Err(VslError::BadArguments),
+    }
+}
+
+
+impl VslStream {
+    pub fn with_seed_words(brng: impl Into<StreamBrng>, seeds: &[u32]) -> Result<Self, VslError> {
+        let n = match c_int::try_from(seeds.len()) {
+            Ok(n) if n > 0 => n,
+            _ => return Err(VslError::BadNSeeds),
+        };
+        let mut state = std::ptr::null_mut();
+        vsl_status(unsafe { sys::vslNewStreamEx(&mut state, brng.into().into(), n, seeds.as_ptr()) })?;
+        Ok(Self { state })
+    }
+
+    pub fn with_seed(seed: &VslSeed) -> Result<Self, VslError> {
+        Self::with_seed_words(seed.brng(), seed.words())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{free_buffers, VslRngMethodUniform};
+
+    fn draws(stream: &VslStream) -> [f64; 8] {
+        let mut r = [0.0; 8];
+        stream.uniform(VslRngMethodUniform::Fast, &mut r, 0.0, 1.0).unwrap();
+        r
+    }
+
+    #[test]
+    fn test_with_seed_words() {
+        for brng in [VslBrng::MT19937, VslBrng::SFMT19937, VslBrng::Philox4x32x10] {
+            let single = VslStream::with_seed_words(brng, &[21]).unwrap();
+            assert_eq!(draws(&single), draws(&VslStream::new(brng, 21).unwrap()));
+
+            let wide = VslStream::with_seed_words(brng, &[21, 1, 2, 3]).unwrap();
+            assert_ne!(draws(&wide), draws(&VslStream::new(brng, 21).unwrap()));
+        }
+
+        assert_eq!(VslStream::with_seed_words(VslBrng::MT19937, &[]).err(), Some(VslError::BadNSeeds));
+
+        free_buffers();
+    }
+
+    #[test]
+    fn test_seed_builder() {
+        let philox = VslSeed::philox(21, 0);
+        assert_eq!(philox.words(), [21, 0, 0, 0, 0, 0]);
+        let stream = VslStream::with_seed(&philox).unwrap();
+        assert_eq!(draws(&stream).last().unwrap().clone(), 0.969321598066017);
+
+        let philox = VslSeed::philox(1 << 32, 5 << 96);
+        assert_eq!(philox.words(), [0, 1, 0, 0, 0, 5]);
+
+        let sobol = VslStream::with_seed(&VslSeed::sobol(4)).unwrap();
+        assert_eq!(draws(&sobol), draws(&VslStream::new(VslBrng::Sobol, 4).unwrap()));
+
+        assert_eq!(VslSeed::sobol_with_direction_numbers(2, &[1; 63]), Err(VslError::BadArguments));
+        let seed = VslSeed::niederreiter_with_direction_numbers(1, &[1 << 31; 32]).unwrap();
+        assert_eq!(seed.words()[..3], [1, sys::VSL_USER_QRNG_INITIAL_VALUES, sys::VSL_USER_DIRECTION_NUMBERS]);
+        assert_eq!(seed.brng(), StreamBrng::from(VslBrng::Niederreiter));
+
+        free_buffers();
+    }
+}
@@ -0,0 +1,207 @@
+use std::{ffi::{c_int, c_uint}, mem::size_of, panic::{self, AssertUnwindSafe}, ptr, slice};
+
+use crate::{sys, RegisteredBrng, VslError};
+use super::vsl_check;
+
+
+/// A basic generator implemented in Rust that can be registered with `register_brng`.
+///
+/// MKL allocates, copies, saves and loads stream states as raw bytes, so the generator state has
+/// to be `Copy`, and it has to be `Send` because the `VslStream` holding it is. Leapfrog and
+/// skip-ahead are reported as unsupported.
+///
+/// # Safety
+///
+/// `VslStream::from_bytes`, `load_from_path` and deserialization copy arbitrary bytes into the
+/// state, so every byte pattern of `size_of::<Self>()` bytes must be a valid `Self`: no
+/// references, `bool`s, enums or `NonZero*` fields.
+pub unsafe trait UserBrng: Copy + Send + 'static {
+    /// Number of 32-bit words of initialization parameters the generator expects.
+    const N_SEEDS: usize;
+    const INCLUDES_ZERO: bool = true;
+    /// Number of significant bits in each word produced by `next_u32`, between 1 and 32.
+    const N_BITS: u32 = 32;
+
+    fn init(seeds: &[u32]) -> Self;
+
+    fn next_u32(&mut self, r: &mut [u32]);
+
+    fn next_f64(&mut self, r: &mut [f64], a: f64, b: f64) {
+        let mut words = vec![0; r.len()];
+        self.next_u32(&mut words);
+        let scale = (b - a) / (1u64 << Self::N_BITS) as f64;
+        r.iter_mut().zip(words).for_each(|(x, w)| *x = a + w as f64 * scale);
+    }
+
+    fn next_f32(&mut self, r: &mut [f32], a: f32, b: f32) {
+        let mut words = vec![0; r.len()];
+        self.next_u32(&mut words);
+        let shift = Self::N_BITS.saturating_sub(f32::MANTISSA_DIGITS);
+        let scale = (b - a) / (1u64 << (Self::N_BITS - shift)) as f32;
+        r.iter_mut().zip(words).for_each(|(x, w)| *x = a + (w >> shift) as f32 * scale);
+    }
+}
+
+
+// MKL reserves the first 16 bytes of every stream state for its own bookkeeping.
+#[repr(C)]
+struct UserStreamState<B> {
+    reserved: [c_uint; 4],
+    brng: B,
+}
+
+unsafe fn user_state<'a, B>(stream: sys::VSLStreamStatePtr) -> &'a mut B {
+    &mut (*(stream as *mut UserStreamState<B>)).brng
+}
+
+fn guarded(f: impl FnOnce()) -> c_int {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(()) => sys::VSL_STATUS_OK,
+        Err(_) => sys::VSL_ERROR_UNKNOWN,
+    }
+}
+
+unsafe extern "C" fn init_stream<B: UserBrng>(method: c_int, stream: sys::VSLStreamStatePtr, n: c_int, params: *const c_uint) -> c_int {
+    match method as u32 {
+        sys::VSL_INIT_METHOD_STANDARD => {
+            let seeds = match n > 0 && !params.is_null() {
+                true => slice::from_raw_parts(params, n as usize),
+                false => &[],
+            };
+            let state = ptr::addr_of_mut!((*(stream as *mut UserStreamState<B>)).brng);
+            guarded(|| state.write(B::init(seeds)))
+        }
+        sys::VSL_INIT_METHOD_LEAPFROG => sys::VSL_RNG_ERROR_LEAPFROG_UNSUPPORTED,
+        sys::VSL_INIT_METHOD_SKIPAHEAD => sys::VSL_RNG_ERROR_SKIPAHEAD_UNSUPPORTED,
+        sys::VSL_INIT_METHOD_SKIPAHEADEX => sys::VSL_RNG_ERROR_SKIPAHEADEX_UNSUPPORTED,
+        _ => sys::VSL_ERROR_BADARGS,
+    }
+}
+
+unsafe extern "C" fn next_u32<B: UserBrng>(stream: sys::VSLStreamStatePtr, n: c_int, r: *mut c_uint) -> c_int {
+    let r = slice::from_raw_parts_mut(r, n as usize);
+    guarded(|| user_state::<B>(stream).next_u32(r))
+}
+
+unsafe extern "C" fn next_f64<B: UserBrng>(stream: sys::VSLStreamStatePtr, n: c_int, r: *mut f64, a: f64, b: f64) -> c_int {
+    let r = slice::from_raw_parts_mut(r, n as usize);
+    guarded(|| user_state::<B>(stream).next_f64(r, a, b))
+}
+
+unsafe extern "C" fn next_f32<B: UserBrng>(stream: sys::VSLStreamStatePtr, n: c_int, r: *mut f32, a: f32, b: f32) -> c_int {
+    let r = slice::from_raw_parts_mut(r, n as usize);
+    guarded(|| user_state::<B>(stream).next_f32(r, a, b))
+}
+
+
+/// Adds `B` to MKL's BRNG table; the returned id can be passed to `VslStream::new` and
+/// `VslStream::with_seed_words` like any built-in generator.
+pub fn register_brng<B: UserBrng>() -> Result<RegisteredBrng, VslError> {
+    vsl_check((1..=32).contains(&B::N_BITS))?;
+    let properties = sys::VSLBRngProperties {
+        StreamStateSize: size_of::<UserStreamState<B>>().try_into().map_err(|_| VslError::BadArguments)?,
+        NSeeds: B::N_SEEDS.try_into().map_err(|_| VslError::BadArguments)?,
+        IncludesZero: B::INCLUDES_ZERO.into(),
+        WordSize: size_of::<c_uint>() as c_int,
+        NBits: B::N_BITS.try_into().map_err(|_| VslError::BadArguments)?,
+        InitStream: Some(init_stream::<B>),
+        sBRng: Some(next_f32::<B>),
+        dBRng: Some(next_f64::<B>),
+        iBRng: Some(next_u32::<B>),
+    };
+    match unsafe { sys::vslRegisterBrng(&properties) } {
+        id if id < 0 => Err(VslError::try_from(id).unwrap()),
+        id => Ok(RegisteredBrng::new(id)),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{free_buffers, BrngId, StreamBrng, VslRngMethodGaussian, VslRngMethodUniformBits, VslStream};
+
+    #[derive(Copy, Clone)]
+    struct XorShift32(u32);
+
+    impl XorShift32 {
+        fn step(&mut self) -> u32 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 17;
+            self.0 ^= self.0 << 5;
+            self.0
+        }
+    }
+
+    unsafe impl UserBrng for XorShift32 {
+        const N_SEEDS: usize = 1;
+        const INCLUDES_ZERO: bool = false;
+
+        fn init(seeds: &[u32]) -> Self {
+            Self(seeds.first().copied().filter(|&s| s != 0).unwrap_or(1))
+        }
+
+        fn next_u32(&mut self, r: &mut [u32]) {
+            r.iter_mut().for_each(|x| *x = self.step());
+        }
+    }
+
+    #[test]
+    fn test_register_brng() {
+        let id = register_brng::<XorShift32>().unwrap();
+        assert_eq!(StreamBrng::try_from(i32::from(id)), Ok(id.into()));
+        assert!(BrngId::try_from(i32::from(id)).is_err());
+
+        let properties = id.properties().unwrap();
+        assert_eq!(properties.word_size, 4);
+        assert_eq!(properties.n_bits, 32);
+        assert!(!properties.includes_zero);
+
+        let stream = VslStream::new(id, 7).unwrap();
+        assert_eq!(stream.brng(), Ok(id.into()));
+
+        let mut expected = XorShift32::init(&[7]);
+        let mut bits = [0u32; 16];
+        stream.uniform_bits(VslRngMethodUniformBits::Std, &mut bits).unwrap();
+        assert!(bits.iter().all(|&x| x == expected.step()));
+
+        let mut r = vec![0.0f64; 10000];
+        stream.gaussian(VslRngMethodGaussian::BoxMuller2, &mut r, 1.0, 2.0).unwrap();
+        let mean = r.iter().sum::<f64>() / r.len() as f64;
+        assert!((mean - 1.0).abs() < 0.1);
+
+        let mut copy = stream.clone();
+        assert_eq!(copy.skip_ahead(10), Err(VslError::SkipaheadUnsupported));
+
+        free_buffers();
+    }
+
+    #[derive(Copy, Clone)]
+    struct Wide(XorShift32);
+
+    unsafe impl UserBrng for Wide {
+        const N_SEEDS: usize = 1;
+        const N_BITS: u32 = 64;
+
+        fn init(seeds: &[u32]) -> Self {
+            Self(XorShift32::init(seeds))
+        }
+
+        fn next_u32(&mut self, r: &mut [u32]) {
+            self.0.next_u32(r)
+        }
+    }
+
+    #[test]
+    fn test_register_invalid_bits() {
+        assert_eq!(register_brng::<Wide>(), Err(VslError::BadArguments));
+    }
+
+    #[test]
+    fn test_default_float_batches() {
+        let mut brng = XorShift32::init(&[3]);
+        let mut r = [0.0f32; 1000];
+        brng.next_f32(&mut r, -1.0, 1.0);
+        assert!(r.iter().all(|x| (-1.0..1.0).contains(x)));
+    }
+}